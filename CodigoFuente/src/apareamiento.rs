@@ -0,0 +1,111 @@
+use crate::models::Animal;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Como se aparea una especie
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum SistemaApareamiento {
+    // Cualquier macho adulto cercano sirve (conejos)
+    Promiscuo,
+    // Las hembras buscan al macho mas pesado que este cerca, y cada macho
+    // cubre como maximo hembras_por_macho hembras por dia (manadas de cabras y vacas)
+    Poliginico { hembras_por_macho: u32 },
+}
+
+// Elige el padre de las crias de una hembra entre los machos adultos de su especie y municipio.
+// cubiertas dice cuantas hembras lleva cada macho en el dia (por indice en presas).
+// Devuelve None si no hay ningun macho disponible a menos de radio metros
+pub fn elegir_padre<R: Rng>(
+    hembra: &Animal,
+    machos: &[usize],
+    presas: &[Animal],
+    cubiertas: &[u32],
+    sistema: &SistemaApareamiento,
+    radio: f32,
+    rng: &mut R,
+) -> Option<usize> {
+    let cercanos = machos
+        .iter()
+        .copied()
+        .filter(|&i| presas[i].posicion.distancia(&hembra.posicion) <= radio);
+    match sistema {
+        SistemaApareamiento::Promiscuo => cercanos.collect::<Vec<_>>().choose(rng).copied(),
+        SistemaApareamiento::Poliginico { hembras_por_macho } => cercanos
+            .filter(|&i| cubiertas[i] < *hembras_por_macho)
+            .max_by(|&a, &b| presas[a].peso_kg.total_cmp(&presas[b].peso_kg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::especies::ConfigEspecies;
+    use crate::mapa::Posicion;
+    use crate::models::{Especie, Sexo};
+    use crate::region::Municipio;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // Cabras adultas en fila sobre el eje x: (sexo, x, peso)
+    fn cabras(datos: &[(Sexo, f32, f32)]) -> Vec<Animal> {
+        let parametros = ConfigEspecies::default().cabra;
+        datos
+            .iter()
+            .enumerate()
+            .map(|(id, (sexo, x, peso))| {
+                let posicion = Posicion { x: *x, y: 0.0 };
+                let mut cabra = Animal::new(id as u32, Especie::Cabra, 1000, sexo.clone(), Municipio::Medellin, posicion, &parametros);
+                cabra.peso_kg = *peso;
+                cabra
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sin_machos_cerca_no_hay_padre() {
+        let presas = cabras(&[(Sexo::Hembra, 0.0, 40.0), (Sexo::Macho, 500.0, 60.0)]);
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for sistema in [SistemaApareamiento::Promiscuo, SistemaApareamiento::Poliginico { hembras_por_macho: 3 }] {
+            assert_eq!(elegir_padre(&presas[0], &[1], &presas, &[0, 0], &sistema, 100.0, &mut rng), None);
+            assert_eq!(elegir_padre(&presas[0], &[], &presas, &[0, 0], &sistema, 1000.0, &mut rng), None);
+            assert_eq!(elegir_padre(&presas[0], &[1], &presas, &[0, 0], &sistema, 1000.0, &mut rng), Some(1));
+        }
+    }
+
+    #[test]
+    fn poliginico_escoge_al_macho_mas_pesado() {
+        let presas = cabras(&[
+            (Sexo::Hembra, 0.0, 40.0),
+            (Sexo::Macho, 10.0, 55.0),
+            (Sexo::Macho, 20.0, 70.0),
+            (Sexo::Macho, 30.0, 60.0),
+            (Sexo::Macho, 900.0, 90.0), // El mas pesado, pero lejos
+        ]);
+        let sistema = SistemaApareamiento::Poliginico { hembras_por_macho: 3 };
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let padre = elegir_padre(&presas[0], &[1, 2, 3, 4], &presas, &[0; 5], &sistema, 100.0, &mut rng);
+        assert_eq!(padre, Some(2));
+    }
+
+    #[test]
+    fn un_macho_no_cubre_mas_de_hembras_por_macho_al_dia() {
+        let mut datos = vec![(Sexo::Macho, 0.0, 70.0), (Sexo::Macho, 5.0, 50.0)];
+        datos.extend((0..5).map(|_| (Sexo::Hembra, 10.0, 40.0)));
+        let presas = cabras(&datos);
+        let sistema = SistemaApareamiento::Poliginico { hembras_por_macho: 2 };
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        // Igual que en Mundo::paso_dia, cada preñez le suma una hembra cubierta al padre
+        let mut cubiertas = vec![0; presas.len()];
+        let mut padres = Vec::new();
+        for hembra in &presas[2..] {
+            let padre = elegir_padre(hembra, &[0, 1], &presas, &cubiertas, &sistema, 100.0, &mut rng);
+            if let Some(i) = padre {
+                cubiertas[i] += 1;
+            }
+            padres.push(padre);
+        }
+        assert_eq!(padres, [Some(0), Some(0), Some(1), Some(1), None]);
+    }
+}
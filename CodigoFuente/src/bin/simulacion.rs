@@ -0,0 +1,267 @@
+// Simulador por consola: corre el mundo sin ventana, tan rapido como se pueda,
+// para usarlo en servidores o en scripts
+//
+// Ejemplo:
+//   cargo run --release --bin simulacion -- --depredadores 1 --conejos 50 --dias 365 --semilla 42
+use predator_prey::especies::ConfigEspecies;
+use predator_prey::estrategia::Estrategia;
+use predator_prey::fase::{self, EjePresas};
+use predator_prey::lotka_volterra::ModeloLotkaVolterra;
+use predator_prey::mapa::Mapa;
+use predator_prey::models::Especie;
+use predator_prey::world::Mundo;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+const AYUDA: &str = "\
+Uso: simulacion [opciones]
+
+Opciones:
+  --depredadores N       Depredadores iniciales (por defecto 1)
+  --max-depredadores N   Maximo de depredadores vivos a la vez (por defecto sin limite)
+  --estrategia NOMBRE    Estrategia de caza de los depredadores iniciales: mas_pesada, al_azar,
+                         mas_joven, mas_cercana o forrajeo_optimo (por defecto la del archivo
+                         de especies)
+  --depredadores-con NOMBRE:N
+                         Agrega N depredadores mas con esa estrategia (se puede repetir)
+  --conejos N            Conejos iniciales (por defecto 0)
+  --cabras N             Cabras iniciales (por defecto 0)
+  --vacas N              Vacas iniciales (por defecto 0)
+  --dias N               Dias a simular (por defecto 30)
+  --semilla N            Semilla del generador aleatorio (por defecto al azar)
+  --mapa ANCHOxALTO      Tamano del mapa en metros (por defecto 1000x1000)
+  --especies ARCHIVO     Parametros de las especies en TOML (por defecto los de especies.toml)
+  --salida ARCHIVO       Escribe los reportes en ARCHIVO en vez de la consola
+  --csv ARCHIVO          Al terminar escribe la serie de tiempo (un dia por fila) en ARCHIVO
+  --fase ARCHIVO         Al terminar dibuja el retrato de fase (depredadores contra presas) en
+                         ARCHIVO, en formato SVG
+  --fase-biomasa ARCHIVO Igual que --fase pero con la biomasa de presas en vez de la cantidad
+  --cargar ARCHIVO       Sigue un mundo guardado. No se puede combinar con las opciones que arman
+                         el mundo (animales, estrategias, semilla, mapa ni especies), que ya
+                         vienen en el archivo
+  --guardar ARCHIVO      Guarda el mundo al terminar, para seguirlo despues
+  --lotka-volterra       Despues de cada dia escribe lo que predicen las ecuaciones de
+                         Lotka-Volterra, arrancando con los mismos animales
+  -h, --ayuda            Muestra esta ayuda";
+
+// Lo que se puede pedir por linea de comandos
+struct Opciones {
+    depredadores: u32,
+    max_depredadores: Option<u32>,
+    estrategia: Option<Estrategia>,
+    depredadores_con: Vec<(Estrategia, u32)>,
+    conejos: u32,
+    cabras: u32,
+    vacas: u32,
+    dias: u32,
+    semilla: Option<u64>,
+    mapa: Mapa,
+    especies: Option<String>,
+    salida: Option<String>,
+    csv: Option<String>,
+    fase: Option<(String, EjePresas)>,
+    cargar: Option<String>,
+    guardar: Option<String>,
+    lotka_volterra: bool,
+}
+
+impl Default for Opciones {
+    fn default() -> Self {
+        Self {
+            depredadores: 1,
+            max_depredadores: None,
+            estrategia: None,
+            depredadores_con: Vec::new(),
+            conejos: 0,
+            cabras: 0,
+            vacas: 0,
+            dias: 30,
+            semilla: None,
+            mapa: Mapa::default(),
+            especies: None,
+            salida: None,
+            csv: None,
+            fase: None,
+            cargar: None,
+            guardar: None,
+            lotka_volterra: false,
+        }
+    }
+}
+
+// Opciones que arman el mundo inicial. Un mundo cargado ya trae todo esto
+const OPCIONES_DEL_MUNDO: [&str; 10] = [
+    "--depredadores",
+    "--max-depredadores",
+    "--estrategia",
+    "--depredadores-con",
+    "--conejos",
+    "--cabras",
+    "--vacas",
+    "--semilla",
+    "--mapa",
+    "--especies",
+];
+
+// Lee los argumentos, devuelve None si solo se pidio la ayuda
+fn leer_opciones(args: impl Iterator<Item = String>) -> Result<Option<Opciones>, String> {
+    let mut opciones = Opciones::default();
+    let mut args = args;
+    let mut del_mundo = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--ayuda" {
+            return Ok(None);
+        }
+        // Las opciones sin valor
+        if arg == "--lotka-volterra" {
+            opciones.lotka_volterra = true;
+            continue;
+        }
+        let valor = args
+            .next()
+            .ok_or_else(|| format!("falta el valor de {}", arg))?;
+        if OPCIONES_DEL_MUNDO.contains(&arg.as_str()) {
+            del_mundo.push(arg.clone());
+        }
+        match arg.as_str() {
+            "--depredadores" => opciones.depredadores = leer_numero(&arg, &valor)?,
+            "--max-depredadores" => opciones.max_depredadores = Some(leer_numero(&arg, &valor)?),
+            "--estrategia" => opciones.estrategia = Some(valor.parse()?),
+            "--depredadores-con" => opciones.depredadores_con.push(leer_depredadores_con(&valor)?),
+            "--conejos" => opciones.conejos = leer_numero(&arg, &valor)?,
+            "--cabras" => opciones.cabras = leer_numero(&arg, &valor)?,
+            "--vacas" => opciones.vacas = leer_numero(&arg, &valor)?,
+            "--dias" => opciones.dias = leer_numero(&arg, &valor)?,
+            "--semilla" => opciones.semilla = Some(leer_numero(&arg, &valor)?),
+            "--mapa" => opciones.mapa = leer_mapa(&valor)?,
+            "--especies" => opciones.especies = Some(valor),
+            "--salida" => opciones.salida = Some(valor),
+            "--csv" => opciones.csv = Some(valor),
+            "--fase" => opciones.fase = Some((valor, EjePresas::Cantidad)),
+            "--fase-biomasa" => opciones.fase = Some((valor, EjePresas::Biomasa)),
+            "--cargar" => opciones.cargar = Some(valor),
+            "--guardar" => opciones.guardar = Some(valor),
+            _ => return Err(format!("opcion desconocida: {}", arg)),
+        }
+    }
+    if opciones.cargar.is_some() && !del_mundo.is_empty() {
+        return Err(format!(
+            "--cargar no se puede usar con {}: el mundo guardado ya trae sus animales, semilla, mapa y especies",
+            del_mundo.join(", ")
+        ));
+    }
+    Ok(Some(opciones))
+}
+
+fn leer_numero<T: std::str::FromStr>(opcion: &str, valor: &str) -> Result<T, String> {
+    valor
+        .parse()
+        .map_err(|_| format!("valor invalido para {}: {}", opcion, valor))
+}
+
+// El mapa se escribe como ANCHOxALTO, por ejemplo 2000x500
+fn leer_mapa(valor: &str) -> Result<Mapa, String> {
+    let (ancho, alto) = valor
+        .split_once('x')
+        .ok_or_else(|| format!("el mapa debe ser ANCHOxALTO, no {}", valor))?;
+    let ancho: f32 = leer_numero("--mapa", ancho)?;
+    let alto: f32 = leer_numero("--mapa", alto)?;
+    // "inf" y "nan" se leen como numeros, Mapa::new los rechaza
+    Mapa::new(ancho, alto)
+}
+
+// Se escribe como NOMBRE:N, por ejemplo forrajeo_optimo:3
+fn leer_depredadores_con(valor: &str) -> Result<(Estrategia, u32), String> {
+    let (nombre, cantidad) = valor
+        .split_once(':')
+        .ok_or_else(|| format!("--depredadores-con debe ser NOMBRE:N, no {}", valor))?;
+    Ok((nombre.parse()?, leer_numero("--depredadores-con", cantidad)?))
+}
+
+fn main() {
+    let opciones = match leer_opciones(std::env::args().skip(1)) {
+        Ok(Some(opciones)) => opciones,
+        Ok(None) => {
+            println!("{}", AYUDA);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, AYUDA);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = correr(&opciones) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn correr(opciones: &Opciones) -> Result<(), Box<dyn Error>> {
+    let mut mundo = match &opciones.cargar {
+        Some(ruta) => Mundo::cargar(ruta)?,
+        None => crear_mundo(opciones)?,
+    };
+    // Las estrategias propias solo se pueden registrar desde codigo, aqui no hay como
+    let sin_registrar = mundo.estrategias_sin_registrar();
+    if !sin_registrar.is_empty() {
+        return Err(format!(
+            "el mundo guardado usa estrategias propias que este programa no conoce: {}",
+            sin_registrar.join(", ")
+        )
+        .into());
+    }
+
+    let mut salida: Box<dyn Write> = match &opciones.salida {
+        Some(ruta) => Box::new(BufWriter::new(File::create(ruta)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    // La semilla siempre se imprime para poder repetir la corrida
+    writeln!(salida, "Semilla: {}", mundo.semilla)?;
+    let mut modelo = opciones.lotka_volterra.then(|| ModeloLotkaVolterra::desde_mundo(&mundo));
+    for _ in 0..opciones.dias {
+        let reporte = mundo.paso_dia();
+        write!(salida, "{}", reporte)?;
+        if let Some(modelo) = &mut modelo {
+            writeln!(salida, "{}", modelo.paso_dia())?;
+        }
+    }
+    salida.flush()?;
+
+    if let Some(ruta) = &opciones.csv {
+        mundo.historial.guardar_csv(ruta)?;
+    }
+    if let Some((ruta, eje)) = &opciones.fase {
+        fase::guardar_svg(&mundo.historial, *eje, ruta)?;
+    }
+
+    if let Some(ruta) = &opciones.guardar {
+        mundo.guardar(ruta)?;
+    }
+    Ok(())
+}
+
+fn crear_mundo(opciones: &Opciones) -> Result<Mundo, Box<dyn Error>> {
+    let especies = match &opciones.especies {
+        Some(ruta) => ConfigEspecies::cargar(ruta)?,
+        None => ConfigEspecies::default(),
+    };
+    let semilla = opciones.semilla.unwrap_or_else(rand::random);
+    let mut mundo = Mundo::with_config(semilla, especies);
+    mundo.reglas.max_depredadores = opciones.max_depredadores;
+    mundo.mapa = opciones.mapa.clone();
+    match &opciones.estrategia {
+        Some(estrategia) => mundo.agregar_depredadores_con(estrategia.clone(), opciones.depredadores)?,
+        None => mundo.agregar_depredadores(opciones.depredadores),
+    }
+    for (estrategia, cantidad) in &opciones.depredadores_con {
+        mundo.agregar_depredadores_con(estrategia.clone(), *cantidad)?;
+    }
+    mundo.agregar_presas(Especie::Conejo, opciones.conejos);
+    mundo.agregar_presas(Especie::Cabra, opciones.cabras);
+    mundo.agregar_presas(Especie::Vaca, opciones.vacas);
+    Ok(mundo)
+}
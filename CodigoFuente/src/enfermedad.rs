@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// Estado de salud de una presa (modelo SIR)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum EstadoSalud {
+    #[default]
+    Susceptible,
+    // Enferma y contagiando, la infeccion termina cuando dias_restantes llega a 0
+    Infectado { dias_restantes: u32 },
+    // Sobrevivio a la enfermedad y ya no se vuelve a contagiar
+    Recuperado,
+}
+
+/// Como termino la infeccion de una presa
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FinInfeccion {
+    Murio,
+    Recupero,
+}
+
+/// Como se contagia y que tan grave es la enfermedad de una especie
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosEnfermedad {
+    // Probabilidad diaria de contagio por cada vecino infectado de la misma especie
+    pub tasa_contagio: f32,
+    // Solo contagian los infectados del mismo municipio a esta distancia o menos (metros)
+    pub radio_contagio: f32,
+    // Dias que dura la infeccion
+    pub dias_infeccion: u32,
+    // Fraccion de los infectados que mueren al terminar la infeccion
+    pub letalidad: f32,
+}
+
+impl ParametrosEnfermedad {
+    // Probabilidad de que una presa susceptible se contagie hoy, con `infectados` vecinos
+    // infectados y una probabilidad `externa` de traer la enfermedad de afuera.
+    // factor_estacion sube o baja las dos segun la temporada
+    pub fn probabilidad_contagio(&self, infectados: usize, externa: f32, factor_estacion: f32) -> f32 {
+        let externa = (externa * factor_estacion).min(1.0);
+        let tasa = (self.tasa_contagio * factor_estacion).min(1.0);
+        let escapa = (1.0 - externa) * (1.0 - tasa).powi(infectados as i32);
+        (1.0 - escapa).clamp(0.0, 1.0)
+    }
+}
+
+/// Cuantas presas de una especie hay en cada estado
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConteoSir {
+    pub susceptibles: usize,
+    pub infectados: usize,
+    pub recuperados: usize,
+}
+
+impl ConteoSir {
+    pub fn contar(&mut self, salud: &EstadoSalud) {
+        match salud {
+            EstadoSalud::Susceptible => self.susceptibles += 1,
+            EstadoSalud::Infectado { .. } => self.infectados += 1,
+            EstadoSalud::Recuperado => self.recuperados += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enfermedad(tasa_contagio: f32) -> ParametrosEnfermedad {
+        ParametrosEnfermedad { tasa_contagio, radio_contagio: 100.0, dias_infeccion: 10, letalidad: 0.3 }
+    }
+
+    #[test]
+    fn sin_infectados_solo_queda_la_probabilidad_externa() {
+        let e = enfermedad(0.05);
+        assert!((e.probabilidad_contagio(0, 0.0005, 1.0) - 0.0005).abs() < 1e-6);
+        assert!((e.probabilidad_contagio(0, 0.0005, 2.0) - 0.001).abs() < 1e-6);
+        assert_eq!(e.probabilidad_contagio(0, 0.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn mas_vecinos_infectados_suben_la_probabilidad() {
+        let e = enfermedad(0.05);
+        let mut anterior = e.probabilidad_contagio(0, 0.0005, 1.0);
+        for infectados in 1..=50 {
+            let p = e.probabilidad_contagio(infectados, 0.0005, 1.0);
+            assert!(p > anterior, "{infectados} infectados: {p} <= {anterior}");
+            anterior = p;
+        }
+    }
+
+    #[test]
+    fn la_probabilidad_queda_entre_cero_y_uno() {
+        for tasa in [0.0, 0.05, 0.5, 1.0] {
+            for externa in [0.0, 0.0005, 0.5, 1.0] {
+                for factor in [0.0, 0.5, 1.0, 3.0, 100.0] {
+                    for infectados in [0, 1, 10, 1000, 100_000] {
+                        let p = enfermedad(tasa).probabilidad_contagio(infectados, externa, factor);
+                        assert!((0.0..=1.0).contains(&p), "tasa {tasa}, externa {externa}, factor {factor}: {p}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use crate::apareamiento::SistemaApareamiento;
+use crate::enfermedad::ParametrosEnfermedad;
+use crate::estaciones::{Estacion, ParametrosEstaciones};
+use crate::estrategia::Estrategia;
+use crate::models::{ComportamientoAnimal, Especie, Gompertz};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Los valores de siempre, vienen dentro del programa
+const ESPECIES_POR_DEFECTO: &str = include_str!("../especies.toml");
+
+/// Que tan dificil es cazar a una presa de la especie
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosDefensa {
+    // Probabilidad de exito de un ataque a un adulto de peso normal, sola
+    pub exito_base: f32,
+    // Multiplica el exito cuando la presa ya es vieja (el ultimo 20% de su vida)
+    pub factor_vejez: f32,
+    // Cada vecino de la manada a radio_manada metros o menos divide el exito por (1 + efecto_manada).
+    // Con 0 la manada no protege
+    pub efecto_manada: f32,
+    pub radio_manada: f32,
+}
+
+/// Parametros biologicos de una especie de presa
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosEspecie {
+    pub edad_adulta: u32,
+    pub edad_maxima: u32,
+    pub edad_sacrificio: u32,
+    pub tasa_reproduccion_diaria: f32,
+    // Probabilidad diaria de contagiarse sin ningun vecino infectado (la enfermedad llega de afuera)
+    pub probabilidad_enfermedad: f32,
+    pub enfermedad: ParametrosEnfermedad,
+    pub max_crias_por_parto: u8,
+    pub crecimiento: Gompertz,
+    // Metros que puede caminar en un dia
+    pub desplazamiento_diario: f32,
+    // Probabilidad diaria de irse a un municipio vecino
+    pub probabilidad_migracion: f32,
+    // Forraje que come al dia, como fraccion de su peso
+    pub consumo_forraje: f32,
+    // Dias seguidos que aguanta comiendo menos de la mitad de lo que necesita
+    pub dias_max_desnutrido: u32,
+    pub apareamiento: SistemaApareamiento,
+    // Una hembra solo se aparea con machos a esta distancia o menos (metros)
+    pub radio_apareamiento: f32,
+    // Dias de preñez y dias despues del parto en que no puede volver a quedar preñada
+    pub dias_gestacion: u32,
+    pub dias_posparto: u32,
+    pub defensa: ParametrosDefensa,
+    // Fraccion del peso que es carne que el depredador puede comer (el resto es hueso, piel...)
+    pub fraccion_comestible: f32,
+}
+
+impl ComportamientoAnimal for ParametrosEspecie {
+    fn edad_adulta(&self) -> u32 {
+        self.edad_adulta
+    }
+
+    fn edad_maxima(&self) -> u32 {
+        self.edad_maxima
+    }
+
+    fn edad_sacrificio(&self) -> u32 {
+        self.edad_sacrificio
+    }
+
+    fn tasa_reproduccion_diaria(&self) -> f32 {
+        self.tasa_reproduccion_diaria
+    }
+
+    fn probabilidad_enfermedad(&self) -> f32 {
+        self.probabilidad_enfermedad
+    }
+
+    fn max_crias_por_parto(&self) -> u8 {
+        self.max_crias_por_parto
+    }
+}
+
+/// Parametros del depredador
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosDepredador {
+    pub edad_adulta: u32,
+    pub edad_maxima: u32,
+    pub nivel_minimo_diario: f32,
+    pub nivel_optimo_diario: f32,
+    pub tasa_reproduccion_diaria: f32,
+    pub max_crias_por_parto: u8,
+    // Cuantos dias de consumo optimo debe tener en reserva para reproducirse a la tasa completa
+    pub dias_reserva_reproduccion: f32,
+    // Reserva que la madre le pasa a cada cria al nacer
+    pub reserva_cria_kg: f32,
+    // Metros que puede caminar en un dia
+    pub desplazamiento_diario: f32,
+    // Solo puede cazar presas a esta distancia o menos (metros)
+    pub radio_caza: f32,
+    // Probabilidad diaria de irse a un municipio vecino (el doble si tiene hambre)
+    pub probabilidad_migracion: f32,
+    // Estrategia de caza de los depredadores que se agregan sin pedir otra
+    pub estrategia: Estrategia,
+    // Para el forrajeo optimo: metros por hora persiguiendo y horas comiendo por kg de presa
+    pub velocidad_persecucion: f32,
+    pub manejo_h_por_kg: f32,
+    // Reserva que gasta en un ataque que falla
+    pub costo_caza_fallida_kg: f32,
+    // Kg que alcanza a comer en un dia y maximo de reserva que puede acumular
+    pub capacidad_estomago_kg: f32,
+    pub reserva_maxima_kg: f32,
+    // Fraccion de la carne de un cadaver que se pudre cada dia
+    pub descomposicion_diaria: f32,
+}
+
+/// Parametros de todas las especies, leidos de un archivo TOML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigEspecies {
+    pub conejo: ParametrosEspecie,
+    pub cabra: ParametrosEspecie,
+    pub vaca: ParametrosEspecie,
+    pub depredador: ParametrosDepredador,
+    // Si el archivo no trae la seccion se usan las del especies.toml del programa
+    #[serde(default = "estaciones_por_defecto")]
+    pub estaciones: ParametrosEstaciones,
+}
+
+/// Errores al leer un archivo de especies
+#[derive(Debug)]
+pub enum ErrorConfig {
+    Io(io::Error),
+    Formato(toml::de::Error),
+    Invalido(String),
+}
+
+impl fmt::Display for ErrorConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorConfig::Io(e) => write!(f, "no se pudo leer el archivo de especies: {}", e),
+            ErrorConfig::Formato(e) => write!(f, "el archivo de especies tiene un error: {}", e),
+            ErrorConfig::Invalido(e) => write!(f, "parametro invalido: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ErrorConfig {}
+
+impl From<io::Error> for ErrorConfig {
+    fn from(e: io::Error) -> Self {
+        ErrorConfig::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ErrorConfig {
+    fn from(e: toml::de::Error) -> Self {
+        ErrorConfig::Formato(e)
+    }
+}
+
+impl Default for ConfigEspecies {
+    fn default() -> Self {
+        Self::desde_toml(ESPECIES_POR_DEFECTO).expect("el archivo especies.toml del programa es valido")
+    }
+}
+
+fn estaciones_por_defecto() -> ParametrosEstaciones {
+    ConfigEspecies::default().estaciones
+}
+
+impl ConfigEspecies {
+    // Lee y valida un archivo de especies
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Self, ErrorConfig> {
+        let texto = fs::read_to_string(ruta)?;
+        Self::desde_toml(&texto)
+    }
+
+    pub fn desde_toml(texto: &str) -> Result<Self, ErrorConfig> {
+        let config: ConfigEspecies = toml::from_str(texto)?;
+        config.validar()?;
+        Ok(config)
+    }
+
+    // Parametros de una especie
+    pub fn de(&self, especie: &Especie) -> &ParametrosEspecie {
+        match especie {
+            Especie::Conejo => &self.conejo,
+            Especie::Cabra => &self.cabra,
+            Especie::Vaca => &self.vaca,
+        }
+    }
+
+    // Revisa que los valores tengan sentido antes de usarlos en la simulacion
+    pub fn validar(&self) -> Result<(), ErrorConfig> {
+        for (nombre, p) in [("conejo", &self.conejo), ("cabra", &self.cabra), ("vaca", &self.vaca)] {
+            let invalido = |msg: &str| Err(ErrorConfig::Invalido(format!("{}.{}", nombre, msg)));
+            let (e, c, df) = (&p.enfermedad, &p.crecimiento, &p.defensa);
+            revisar_finitos(
+                nombre,
+                &[
+                    ("tasa_reproduccion_diaria", p.tasa_reproduccion_diaria),
+                    ("probabilidad_enfermedad", p.probabilidad_enfermedad),
+                    ("enfermedad.tasa_contagio", e.tasa_contagio),
+                    ("enfermedad.radio_contagio", e.radio_contagio),
+                    ("enfermedad.letalidad", e.letalidad),
+                    ("crecimiento.a", c.a),
+                    ("crecimiento.b", c.b),
+                    ("crecimiento.k", c.k),
+                    ("desplazamiento_diario", p.desplazamiento_diario),
+                    ("probabilidad_migracion", p.probabilidad_migracion),
+                    ("consumo_forraje", p.consumo_forraje),
+                    ("radio_apareamiento", p.radio_apareamiento),
+                    ("defensa.exito_base", df.exito_base),
+                    ("defensa.factor_vejez", df.factor_vejez),
+                    ("defensa.efecto_manada", df.efecto_manada),
+                    ("defensa.radio_manada", df.radio_manada),
+                    ("fraccion_comestible", p.fraccion_comestible),
+                ],
+            )?;
+            if p.edad_maxima == 0 {
+                return invalido("edad_maxima debe ser mayor que 0");
+            }
+            if p.edad_adulta >= p.edad_maxima {
+                return invalido("edad_adulta debe ser menor que edad_maxima");
+            }
+            if p.edad_sacrificio >= p.edad_maxima {
+                return invalido("edad_sacrificio debe ser menor que edad_maxima");
+            }
+            if !(0.0..=1.0).contains(&p.tasa_reproduccion_diaria) {
+                return invalido("tasa_reproduccion_diaria debe estar entre 0 y 1");
+            }
+            if !(0.0..=1.0).contains(&p.probabilidad_enfermedad) {
+                return invalido("probabilidad_enfermedad debe estar entre 0 y 1");
+            }
+            if !(0.0..=1.0).contains(&e.tasa_contagio) {
+                return invalido("enfermedad.tasa_contagio debe estar entre 0 y 1");
+            }
+            if e.radio_contagio < 0.0 {
+                return invalido("enfermedad.radio_contagio no puede ser negativo");
+            }
+            if e.dias_infeccion == 0 {
+                return invalido("enfermedad.dias_infeccion debe ser al menos 1");
+            }
+            if !(0.0..=1.0).contains(&e.letalidad) {
+                return invalido("enfermedad.letalidad debe estar entre 0 y 1");
+            }
+            if p.max_crias_por_parto == 0 {
+                return invalido("max_crias_por_parto debe ser al menos 1");
+            }
+            if !(c.a > 0.0 && c.b > 0.0 && c.k > 0.0) {
+                return invalido("crecimiento debe tener a, b y k mayores que 0");
+            }
+            if p.desplazamiento_diario < 0.0 {
+                return invalido("desplazamiento_diario no puede ser negativo");
+            }
+            if !(0.0..=1.0).contains(&p.probabilidad_migracion) {
+                return invalido("probabilidad_migracion debe estar entre 0 y 1");
+            }
+            if !(0.0..=1.0).contains(&p.consumo_forraje) {
+                return invalido("consumo_forraje debe estar entre 0 y 1");
+            }
+            if !(p.fraccion_comestible > 0.0 && p.fraccion_comestible <= 1.0) {
+                return invalido("fraccion_comestible debe estar entre 0 (sin incluir) y 1");
+            }
+            if !(0.0..=1.0).contains(&df.exito_base) {
+                return invalido("defensa.exito_base debe estar entre 0 y 1");
+            }
+            if df.factor_vejez < 0.0 || df.efecto_manada < 0.0 || df.radio_manada < 0.0 {
+                return invalido("defensa no puede tener valores negativos");
+            }
+            if let SistemaApareamiento::Poliginico { hembras_por_macho: 0 } = p.apareamiento {
+                return invalido("apareamiento.hembras_por_macho debe ser al menos 1");
+            }
+            if p.radio_apareamiento <= 0.0 {
+                return invalido("radio_apareamiento debe ser mayor que 0");
+            }
+            if p.dias_gestacion == 0 {
+                return invalido("dias_gestacion debe ser al menos 1");
+            }
+        }
+
+        for (nombre, estacion) in [
+            ("estaciones.seca", Estacion::Seca),
+            ("estaciones.primeras_lluvias", Estacion::PrimerasLluvias),
+            ("estaciones.veranillo", Estacion::Veranillo),
+            ("estaciones.segundas_lluvias", Estacion::SegundasLluvias),
+        ] {
+            let f = self.estaciones.de(estacion);
+            revisar_finitos(
+                nombre,
+                &[("forraje", f.forraje), ("reproduccion", f.reproduccion), ("enfermedad", f.enfermedad)],
+            )?;
+            if f.forraje < 0.0 || f.reproduccion < 0.0 || f.enfermedad < 0.0 {
+                return Err(ErrorConfig::Invalido(format!(
+                    "estaciones: los factores de {} no pueden ser negativos",
+                    estacion
+                )));
+            }
+        }
+
+        let d = &self.depredador;
+        let invalido = |msg: &str| Err(ErrorConfig::Invalido(format!("depredador.{}", msg)));
+        revisar_finitos(
+            "depredador",
+            &[
+                ("nivel_minimo_diario", d.nivel_minimo_diario),
+                ("nivel_optimo_diario", d.nivel_optimo_diario),
+                ("tasa_reproduccion_diaria", d.tasa_reproduccion_diaria),
+                ("dias_reserva_reproduccion", d.dias_reserva_reproduccion),
+                ("reserva_cria_kg", d.reserva_cria_kg),
+                ("desplazamiento_diario", d.desplazamiento_diario),
+                ("radio_caza", d.radio_caza),
+                ("probabilidad_migracion", d.probabilidad_migracion),
+                ("velocidad_persecucion", d.velocidad_persecucion),
+                ("manejo_h_por_kg", d.manejo_h_por_kg),
+                ("costo_caza_fallida_kg", d.costo_caza_fallida_kg),
+                ("capacidad_estomago_kg", d.capacidad_estomago_kg),
+                ("reserva_maxima_kg", d.reserva_maxima_kg),
+                ("descomposicion_diaria", d.descomposicion_diaria),
+            ],
+        )?;
+        if d.edad_adulta >= d.edad_maxima {
+            return invalido("edad_adulta debe ser menor que edad_maxima");
+        }
+        // Las propias se registran en el mundo, que todavia no existe cuando se lee el archivo
+        if let Estrategia::Propia(nombre) = &d.estrategia {
+            return invalido(&format!("estrategia '{}': el archivo solo acepta las que trae el programa", nombre));
+        }
+        if !(d.nivel_minimo_diario > 0.0 && d.nivel_minimo_diario <= d.nivel_optimo_diario) {
+            return invalido("nivel_minimo_diario debe ser mayor que 0 y no mayor que nivel_optimo_diario");
+        }
+        if !(0.0..=1.0).contains(&d.tasa_reproduccion_diaria) {
+            return invalido("tasa_reproduccion_diaria debe estar entre 0 y 1");
+        }
+        if d.max_crias_por_parto == 0 {
+            return invalido("max_crias_por_parto debe ser al menos 1");
+        }
+        if d.dias_reserva_reproduccion <= 0.0 {
+            return invalido("dias_reserva_reproduccion debe ser mayor que 0");
+        }
+        if d.reserva_cria_kg < 0.0 {
+            return invalido("reserva_cria_kg no puede ser negativa");
+        }
+        if d.desplazamiento_diario < 0.0 {
+            return invalido("desplazamiento_diario no puede ser negativo");
+        }
+        if d.radio_caza <= 0.0 {
+            return invalido("radio_caza debe ser mayor que 0");
+        }
+        if !(0.0..=1.0).contains(&d.probabilidad_migracion) {
+            return invalido("probabilidad_migracion debe estar entre 0 y 1");
+        }
+        if d.velocidad_persecucion <= 0.0 {
+            return invalido("velocidad_persecucion debe ser mayor que 0");
+        }
+        if d.manejo_h_por_kg < 0.0 {
+            return invalido("manejo_h_por_kg no puede ser negativo");
+        }
+        if d.costo_caza_fallida_kg < 0.0 {
+            return invalido("costo_caza_fallida_kg no puede ser negativo");
+        }
+        if d.capacidad_estomago_kg <= 0.0 {
+            return invalido("capacidad_estomago_kg debe ser mayor que 0");
+        }
+        if d.reserva_maxima_kg < d.nivel_optimo_diario {
+            return invalido("reserva_maxima_kg no puede ser menor que nivel_optimo_diario");
+        }
+        // Si no cabe en la reserva nunca se llega a tasa_reproduccion_diaria
+        let reserva_reproduccion = d.nivel_optimo_diario * d.dias_reserva_reproduccion;
+        if d.reserva_maxima_kg < reserva_reproduccion {
+            return invalido(&format!(
+                "reserva_maxima_kg debe ser al menos nivel_optimo_diario * dias_reserva_reproduccion ({})",
+                reserva_reproduccion
+            ));
+        }
+        if !(0.0..=1.0).contains(&d.descomposicion_diaria) {
+            return invalido("descomposicion_diaria debe estar entre 0 y 1");
+        }
+        Ok(())
+    }
+}
+
+// NaN e infinito pasan las comparaciones como `x < 0.0` de validar, por eso se revisan aparte
+fn revisar_finitos(prefijo: &str, campos: &[(&str, f32)]) -> Result<(), ErrorConfig> {
+    match campos.iter().find(|(_, valor)| !valor.is_finite()) {
+        Some((campo, valor)) => Err(ErrorConfig::Invalido(format!(
+            "{}.{} debe ser un numero finito (es {})",
+            prefijo, campo, valor
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // El archivo del programa con un solo valor cambiado
+    fn con_cambio(de: &str, a: &str) -> Result<ConfigEspecies, ErrorConfig> {
+        assert!(ESPECIES_POR_DEFECTO.contains(de), "especies.toml ya no tiene {}", de);
+        ConfigEspecies::desde_toml(&ESPECIES_POR_DEFECTO.replacen(de, a, 1))
+    }
+
+    #[test]
+    fn el_archivo_del_programa_es_valido() {
+        assert!(ConfigEspecies::desde_toml(ESPECIES_POR_DEFECTO).is_ok());
+    }
+
+    #[test]
+    fn sin_la_seccion_estaciones_se_usan_las_del_programa() {
+        let inicio = ESPECIES_POR_DEFECTO.find("[estaciones]").unwrap();
+        let fin = ESPECIES_POR_DEFECTO[inicio..]
+            .find("\n[")
+            .map_or(ESPECIES_POR_DEFECTO.len(), |i| inicio + i + 1);
+        let sin_estaciones = format!("{}{}", &ESPECIES_POR_DEFECTO[..inicio], &ESPECIES_POR_DEFECTO[fin..]);
+        assert!(!sin_estaciones.contains("primeras_lluvias"));
+        let config = ConfigEspecies::desde_toml(&sin_estaciones).unwrap();
+        assert_eq!(config.estaciones, ConfigEspecies::default().estaciones);
+    }
+
+    #[test]
+    fn rechaza_valores_que_no_son_finitos() {
+        for (de, a) in [
+            ("radio_caza = 300.0", "radio_caza = nan"),
+            ("efecto_manada = 0.0", "efecto_manada = inf"),
+            ("factor_vejez = 1.5", "factor_vejez = nan"),
+            ("forraje = 0.5", "forraje = inf"),
+        ] {
+            let resultado = con_cambio(de, a);
+            assert!(matches!(resultado, Err(ErrorConfig::Invalido(_))), "{} se acepto: {:?}", a, resultado);
+        }
+    }
+
+    #[test]
+    fn rechaza_valores_fuera_de_rango() {
+        let resultado = con_cambio("radio_caza = 300.0", "radio_caza = -1.0");
+        assert!(matches!(resultado, Err(ErrorConfig::Invalido(_))));
+        let resultado = con_cambio("exito_base = 0.5", "exito_base = 1.5");
+        assert!(matches!(resultado, Err(ErrorConfig::Invalido(_))));
+    }
+
+    #[test]
+    fn la_reserva_para_reproducirse_cabe_en_la_reserva_maxima() {
+        // 6.0 * 2.0 = 12 kg
+        assert!(con_cambio("reserva_maxima_kg = 15.0", "reserva_maxima_kg = 12.0").is_ok());
+        let resultado = con_cambio("reserva_maxima_kg = 15.0", "reserva_maxima_kg = 11.0");
+        assert!(matches!(resultado, Err(ErrorConfig::Invalido(_))));
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Temporadas del Valle de Aburrá. El clima es bimodal: dos temporadas de lluvia
+/// (marzo-mayo y septiembre-noviembre) separadas por dos secas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Estacion {
+    // Diciembre a febrero, la mas seca del año
+    #[default]
+    Seca,
+    // Marzo a mayo
+    PrimerasLluvias,
+    // Junio a agosto, seca pero mas suave
+    Veranillo,
+    // Septiembre a noviembre, la mas lluviosa (pico en octubre)
+    SegundasLluvias,
+}
+
+impl Estacion {
+    // Temporada de un dia de la simulacion. El dia 1 es el 1 de enero y el año tiene 365 dias
+    pub fn del_dia(dia: u32) -> Estacion {
+        match dia.saturating_sub(1) % 365 {
+            0..=58 => Estacion::Seca,
+            59..=150 => Estacion::PrimerasLluvias,
+            151..=242 => Estacion::Veranillo,
+            243..=333 => Estacion::SegundasLluvias,
+            _ => Estacion::Seca,
+        }
+    }
+}
+
+impl fmt::Display for Estacion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let texto = match self {
+            Estacion::Seca => "Temporada seca",
+            Estacion::PrimerasLluvias => "Primeras lluvias",
+            Estacion::Veranillo => "Veranillo",
+            Estacion::SegundasLluvias => "Segundas lluvias",
+        };
+        write!(f, "{}", texto)
+    }
+}
+
+/// Cuanto multiplica una temporada los valores base de las especies (1.0 = sin cambio)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FactoresEstacion {
+    // Rebrote del forraje
+    pub forraje: f32,
+    // Tasa de reproduccion diaria de las presas
+    pub reproduccion: f32,
+    // Probabilidad de enfermarse y tasa de contagio
+    pub enfermedad: f32,
+}
+
+/// Factores de cada temporada, seccion [estaciones] del archivo de especies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosEstaciones {
+    pub seca: FactoresEstacion,
+    pub primeras_lluvias: FactoresEstacion,
+    pub veranillo: FactoresEstacion,
+    pub segundas_lluvias: FactoresEstacion,
+}
+
+impl ParametrosEstaciones {
+    pub fn de(&self, estacion: Estacion) -> &FactoresEstacion {
+        match estacion {
+            Estacion::Seca => &self.seca,
+            Estacion::PrimerasLluvias => &self.primeras_lluvias,
+            Estacion::Veranillo => &self.veranillo,
+            Estacion::SegundasLluvias => &self.segundas_lluvias,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn las_temporadas_cambian_en_el_dia_correcto() {
+        let cambios = [
+            (59, Estacion::Seca, Estacion::PrimerasLluvias),
+            (151, Estacion::PrimerasLluvias, Estacion::Veranillo),
+            (243, Estacion::Veranillo, Estacion::SegundasLluvias),
+            (334, Estacion::SegundasLluvias, Estacion::Seca),
+        ];
+        // El segundo año repite el calendario
+        for vuelta in 0..2 {
+            for (dia, antes, despues) in cambios {
+                let dia = dia + 365 * vuelta;
+                assert_eq!(Estacion::del_dia(dia), antes, "dia {}", dia);
+                assert_eq!(Estacion::del_dia(dia + 1), despues, "dia {}", dia + 1);
+            }
+        }
+        assert_eq!(Estacion::del_dia(365), Estacion::Seca);
+        assert_eq!(Estacion::del_dia(366), Estacion::Seca);
+    }
+}
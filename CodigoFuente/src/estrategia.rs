@@ -0,0 +1,182 @@
+use crate::especies::ParametrosDepredador;
+use crate::models::{Animal, Depredador};
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Como escoge un depredador cual presa cazar entre las que tiene a su alcance.
+/// Para usar una estrategia nueva sin tocar este archivo se implementa este trait, se registra
+/// con `Mundo::registrar_estrategia` y se asigna con `Estrategia::Propia(nombre)`
+pub trait EstrategiaCaza {
+    // Posicion en candidatas de la presa elegida, None si no quiere ninguna (una posicion que no
+    // existe tambien cuenta como ninguna).
+    // Las candidatas ya estan en su municipio, a su alcance y con edad para ser cazadas
+    fn elegir(
+        &self,
+        depredador: &Depredador,
+        candidatas: &[&Animal],
+        parametros: &ParametrosDepredador,
+        rng: &mut dyn RngCore,
+    ) -> Option<usize>;
+}
+
+/// La estrategia de un depredador: una de las que trae la simulacion (se escogen por nombre en el
+/// archivo de especies) o una propia registrada en el mundo. Es lo que se guarda con el depredador
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Estrategia {
+    // La mas pesada (la de siempre)
+    #[default]
+    MasPesada,
+    AlAzar,
+    // La mas joven de las que ya se pueden cazar
+    MasJoven,
+    MasCercana,
+    // La que da mas kg por hora de persecucion y manejo
+    ForrajeoOptimo,
+    // Registrada con Mundo::registrar_estrategia bajo este nombre
+    Propia(String),
+}
+
+impl Estrategia {
+    pub const TODAS: [Estrategia; 5] = [
+        Estrategia::MasPesada,
+        Estrategia::AlAzar,
+        Estrategia::MasJoven,
+        Estrategia::MasCercana,
+        Estrategia::ForrajeoOptimo,
+    ];
+
+    // Nombre en el archivo de especies y en la linea de comandos
+    pub fn nombre(&self) -> &str {
+        match self {
+            Estrategia::MasPesada => "mas_pesada",
+            Estrategia::AlAzar => "al_azar",
+            Estrategia::MasJoven => "mas_joven",
+            Estrategia::MasCercana => "mas_cercana",
+            Estrategia::ForrajeoOptimo => "forrajeo_optimo",
+            Estrategia::Propia(nombre) => nombre,
+        }
+    }
+}
+
+impl EstrategiaCaza for Estrategia {
+    fn elegir(
+        &self,
+        depredador: &Depredador,
+        candidatas: &[&Animal],
+        parametros: &ParametrosDepredador,
+        rng: &mut dyn RngCore,
+    ) -> Option<usize> {
+        match self {
+            Estrategia::MasPesada => mejor_por(candidatas, |presa| presa.peso_kg, rng),
+            Estrategia::AlAzar => (0..candidatas.len()).collect::<Vec<_>>().choose(rng).copied(),
+            Estrategia::MasJoven => mejor_por(candidatas, |presa| -(presa.edad_dias as f32), rng),
+            Estrategia::MasCercana => {
+                mejor_por(candidatas, |presa| -presa.posicion.distancia(&depredador.posicion), rng)
+            }
+            // Energia sobre tiempo: kg que gana dividido por las horas persiguiendo y comiendo
+            Estrategia::ForrajeoOptimo => mejor_por(
+                candidatas,
+                |presa| {
+                    let persecucion_h = presa.posicion.distancia(&depredador.posicion) / parametros.velocidad_persecucion;
+                    let manejo_h = presa.peso_kg * parametros.manejo_h_por_kg;
+                    presa.peso_kg / (persecucion_h + manejo_h).max(f32::EPSILON)
+                },
+                rng,
+            ),
+            // Las propias las resuelve el registro del mundo, ver RegistroEstrategias::elegir
+            Estrategia::Propia(_) => None,
+        }
+    }
+}
+
+/// Estrategias propias de un mundo, por nombre. No se guardan con el mundo: despues de cargarlo
+/// hay que registrarlas de nuevo, igual que los observadores
+#[derive(Default)]
+pub struct RegistroEstrategias {
+    propias: BTreeMap<String, Box<dyn EstrategiaCaza + Send>>,
+}
+
+impl RegistroEstrategias {
+    pub fn registrar(&mut self, nombre: String, estrategia: Box<dyn EstrategiaCaza + Send>) {
+        self.propias.insert(nombre, estrategia);
+    }
+
+    pub fn contiene(&self, nombre: &str) -> bool {
+        self.propias.contains_key(nombre)
+    }
+
+    // Como EstrategiaCaza::elegir, buscando las propias en el registro.
+    // Un depredador con una propia que no esta registrada no escoge ninguna presa
+    pub fn elegir(
+        &self,
+        estrategia: &Estrategia,
+        depredador: &Depredador,
+        candidatas: &[&Animal],
+        parametros: &ParametrosDepredador,
+        rng: &mut dyn RngCore,
+    ) -> Option<usize> {
+        match estrategia {
+            Estrategia::Propia(nombre) => self.propias.get(nombre)?.elegir(depredador, candidatas, parametros, rng),
+            integrada => integrada.elegir(depredador, candidatas, parametros, rng),
+        }
+    }
+}
+
+/// Se pidio una estrategia propia que no esta registrada en el mundo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstrategiaNoRegistrada(pub String);
+
+impl fmt::Display for EstrategiaNoRegistrada {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "la estrategia '{}' no esta registrada, use Mundo::registrar_estrategia", self.0)
+    }
+}
+
+impl std::error::Error for EstrategiaNoRegistrada {}
+
+// La de mayor puntaje; si hay empate (tolerancia de 0.01) se escoge una al azar
+fn mejor_por(candidatas: &[&Animal], puntaje: impl Fn(&Animal) -> f32, rng: &mut dyn RngCore) -> Option<usize> {
+    let puntajes: Vec<f32> = candidatas.iter().map(|presa| puntaje(presa)).collect();
+    let maximo = puntajes.iter().copied().reduce(f32::max)?;
+    let empatadas: Vec<usize> = (0..puntajes.len())
+        .filter(|&i| (puntajes[i] - maximo).abs() < 0.01)
+        .collect();
+    if empatadas.len() == 1 {
+        Some(empatadas[0])
+    } else {
+        empatadas.choose(rng).copied()
+    }
+}
+
+impl fmt::Display for Estrategia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let texto = match self {
+            Estrategia::MasPesada => "más pesada",
+            Estrategia::AlAzar => "al azar",
+            Estrategia::MasJoven => "más joven",
+            Estrategia::MasCercana => "más cercana",
+            Estrategia::ForrajeoOptimo => "forrajeo óptimo",
+            Estrategia::Propia(nombre) => nombre,
+        };
+        write!(f, "{}", texto)
+    }
+}
+
+impl FromStr for Estrategia {
+    type Err = String;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        Estrategia::TODAS
+            .into_iter()
+            .find(|e| e.nombre() == texto)
+            .ok_or_else(|| {
+                let nombres: Vec<&str> = Estrategia::TODAS.iter().map(|e| e.nombre()).collect();
+                format!("estrategia desconocida '{}' (opciones: {})", texto, nombres.join(", "))
+            })
+    }
+}
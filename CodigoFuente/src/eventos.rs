@@ -0,0 +1,71 @@
+use crate::reporte::{
+    Caza, CazaFallida, Concepcion, Contagio, DiaDepredador, Migracion, MuertePresa, Nacimiento, NacimientoDepredador,
+    Recuperacion,
+};
+
+/// Lo que va pasando dentro de `Mundo::paso_dia`, en el momento en que pasa
+#[derive(Debug, Clone)]
+pub enum Evento {
+    InicioDia { dia: u32 },
+    Caza(Caza),
+    CazaFallida(CazaFallida),
+    // Consumo, hambre o muerte de un depredador
+    Depredador(DiaDepredador),
+    Contagio(Contagio),
+    Recuperacion(Recuperacion),
+    MuerteEnfermedad(MuertePresa),
+    MuerteVejez(MuertePresa),
+    MuerteHambre(MuertePresa),
+    Concepcion(Concepcion),
+    Nacimiento(Nacimiento),
+    NacimientoDepredador(NacimientoDepredador),
+    Migracion(Migracion),
+    FinDia { dia: u32 },
+}
+
+/// Cualquier cosa que quiera enterarse de los eventos del mundo (loggers, estadisticas, la interfaz).
+/// Debe ser Send para que un Mundo con observadores se pueda correr en otro hilo
+pub trait Observador: Send {
+    fn notificar(&mut self, evento: &Evento);
+}
+
+// Asi se puede suscribir un closure directamente
+impl<F: FnMut(&Evento) + Send> Observador for F {
+    fn notificar(&mut self, evento: &Evento) {
+        self(evento)
+    }
+}
+
+/// Identificador que devuelve `Mundo::suscribir`, sirve para quitar la suscripcion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdSuscripcion(u32);
+
+/// Lista de observadores registrados en un mundo
+#[derive(Default)]
+pub struct Observadores {
+    lista: Vec<(IdSuscripcion, Box<dyn Observador>)>,
+    siguiente_id: u32,
+}
+
+impl Observadores {
+    pub fn agregar(&mut self, observador: Box<dyn Observador>) -> IdSuscripcion {
+        let id = IdSuscripcion(self.siguiente_id);
+        self.siguiente_id += 1;
+        self.lista.push((id, observador));
+        id
+    }
+
+    // Devuelve false si no habia ningun observador con ese id
+    pub fn quitar(&mut self, id: IdSuscripcion) -> bool {
+        let antes = self.lista.len();
+        self.lista.retain(|(otro, _)| *otro != id);
+        self.lista.len() != antes
+    }
+
+    // Le avisa a todos los observadores, en el orden en que se suscribieron
+    pub fn emitir(&mut self, evento: &Evento) {
+        for (_, observador) in &mut self.lista {
+            observador.notificar(evento);
+        }
+    }
+}
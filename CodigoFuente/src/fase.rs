@@ -0,0 +1,164 @@
+// Retrato de fase: depredadores contra presas, un punto por dia, unidos en el orden en que
+// pasaron. Los ciclos depredador-presa se ven como espirales u orbitas cerradas
+use crate::historial::{Historial, PuntoHistorial};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Que se pone en el eje de las presas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EjePresas {
+    #[default]
+    Cantidad,
+    Biomasa,
+}
+
+impl EjePresas {
+    pub fn valor(&self, punto: &PuntoHistorial) -> f32 {
+        match self {
+            EjePresas::Cantidad => punto.total_presas() as f32,
+            EjePresas::Biomasa => punto.biomasa_presas_kg,
+        }
+    }
+
+    pub fn titulo(&self) -> &'static str {
+        match self {
+            EjePresas::Cantidad => "Presas",
+            EjePresas::Biomasa => "Biomasa de presas (kg)",
+        }
+    }
+
+    pub fn otro(&self) -> EjePresas {
+        match self {
+            EjePresas::Cantidad => EjePresas::Biomasa,
+            EjePresas::Biomasa => EjePresas::Cantidad,
+        }
+    }
+}
+
+// (presas, depredadores) de cada dia del historial
+pub fn trayectoria(historial: &Historial, eje: EjePresas) -> Vec<(f32, f32)> {
+    historial
+        .puntos
+        .iter()
+        .map(|p| (eje.valor(p), p.depredadores as f32))
+        .collect()
+}
+
+// Maximo de un eje: el valor redondeado hacia arriba a 1, 2 o 5 por una potencia de 10.
+// Tambien la usan las graficas de la interfaz
+pub fn escala_redonda(maximo: f32) -> f32 {
+    if maximo <= 1.0 {
+        return 1.0;
+    }
+    let potencia = 10f32.powf(maximo.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * potencia)
+        .find(|&escala| escala >= maximo)
+        .unwrap_or(10.0 * potencia)
+}
+
+// Tamaño del dibujo y margenes (izquierda, derecha, arriba, abajo)
+const ANCHO: f32 = 640.0;
+const ALTO: f32 = 480.0;
+const MARGENES: (f32, f32, f32, f32) = (70.0, 30.0, 40.0, 60.0);
+
+// El retrato de fase como imagen SVG, con el primer dia en verde y el ultimo resaltado en rojo
+pub fn svg(historial: &Historial, eje: EjePresas) -> String {
+    let mut s = String::new();
+    // Escribir en un String no puede fallar
+    let _ = escribir_svg(&mut s, historial, eje);
+    s
+}
+
+fn escribir_svg(s: &mut String, historial: &Historial, eje: EjePresas) -> fmt::Result {
+    let puntos = trayectoria(historial, eje);
+    let escala_x = escala_redonda(puntos.iter().map(|p| p.0).fold(0.0, f32::max));
+    let escala_y = escala_redonda(puntos.iter().map(|p| p.1).fold(0.0, f32::max));
+    let (izq, der, arriba, abajo) = MARGENES;
+    let (w, h) = (ANCHO - izq - der, ALTO - arriba - abajo);
+    let a_svg = |(x, y): (f32, f32)| (izq + x / escala_x * w, arriba + h - y / escala_y * h);
+
+    writeln!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{ANCHO}" height="{ALTO}" viewBox="0 0 {ANCHO} {ALTO}" font-family="sans-serif" font-size="12">"#
+    )?;
+    writeln!(s, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    writeln!(
+        s,
+        r#"<text x="{}" y="24" text-anchor="middle" font-size="16">Retrato de fase depredador-presa</text>"#,
+        ANCHO / 2.0
+    )?;
+
+    // Ejes con 0, la mitad y el maximo
+    let base = arriba + h;
+    writeln!(s, r#"<line x1="{izq}" y1="{arriba}" x2="{izq}" y2="{base}" stroke="black"/>"#)?;
+    writeln!(s, r#"<line x1="{izq}" y1="{base}" x2="{}" y2="{base}" stroke="black"/>"#, izq + w)?;
+    for paso in 0..=2 {
+        let fraccion = paso as f32 / 2.0;
+        let tx = izq + fraccion * w;
+        let ty = base - fraccion * h;
+        writeln!(s, r#"<line x1="{izq}" y1="{ty}" x2="{}" y2="{ty}" stroke="lightgray"/>"#, izq + w)?;
+        writeln!(s, r#"<line x1="{tx}" y1="{arriba}" x2="{tx}" y2="{base}" stroke="lightgray"/>"#)?;
+        writeln!(
+            s,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            izq - 6.0,
+            ty + 4.0,
+            escala_y * fraccion
+        )?;
+        writeln!(
+            s,
+            r#"<text x="{tx}" y="{}" text-anchor="middle">{}</text>"#,
+            base + 18.0,
+            escala_x * fraccion
+        )?;
+    }
+    writeln!(
+        s,
+        r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+        izq + w / 2.0,
+        ALTO - 15.0,
+        eje.titulo()
+    )?;
+    writeln!(
+        s,
+        r#"<text x="18" y="{}" text-anchor="middle" transform="rotate(-90 18 {})">Depredadores</text>"#,
+        arriba + h / 2.0,
+        arriba + h / 2.0
+    )?;
+
+    if let (Some(&primero), Some(&ultimo)) = (puntos.first(), puntos.last()) {
+        let linea: Vec<String> = puntos
+            .iter()
+            .map(|&p| {
+                let (x, y) = a_svg(p);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        writeln!(
+            s,
+            r#"<polyline points="{}" fill="none" stroke="steelblue" stroke-width="1.5"/>"#,
+            linea.join(" ")
+        )?;
+        let (x, y) = a_svg(primero);
+        writeln!(s, r#"<circle cx="{x:.1}" cy="{y:.1}" r="4" fill="green"/>"#)?;
+        let (x, y) = a_svg(ultimo);
+        writeln!(s, r#"<circle cx="{x:.1}" cy="{y:.1}" r="6" fill="red"/>"#)?;
+        let dia = historial.puntos.last().map_or(0, |p| p.dia);
+        writeln!(
+            s,
+            r#"<text x="{:.1}" y="{:.1}" fill="red">día {}</text>"#,
+            x + 8.0,
+            y - 8.0,
+            dia
+        )?;
+    }
+    writeln!(s, "</svg>")
+}
+
+pub fn guardar_svg(historial: &Historial, eje: EjePresas, ruta: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(ruta, svg(historial, eje))
+}
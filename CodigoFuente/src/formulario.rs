@@ -0,0 +1,268 @@
+// Pantalla de configuracion antes de empezar: todos los campos a la vista con sus valores
+// por defecto, se revisan antes de crear el mundo
+use crate::interfazgraficamacroquad::boton;
+use macroquad::prelude::*;
+use predator_prey::especies::ConfigEspecies;
+use predator_prey::models::Especie;
+use predator_prey::world::Mundo;
+
+// Posicion de cada campo en Formulario::campos
+const DEPREDADORES: usize = 0;
+const CONEJOS: usize = 1;
+const CABRAS: usize = 2;
+const VACAS: usize = 3;
+const SEMILLA: usize = 4;
+const DIAS: usize = 5;
+const ESPECIES: usize = 6;
+
+// Donde empiezan las filas y el alto de cada una
+const Y_CAMPOS: f32 = 90.0;
+const ALTO_FILA: f32 = 62.0;
+const X_ENTRADA: f32 = 360.0;
+const ANCHO_ENTRADA: f32 = 360.0;
+
+// Que se puede escribir en un campo
+enum Tipo {
+    // Entero entre minimo y maximo. Si es opcional se puede dejar vacio
+    Numero { minimo: u64, maximo: u64, opcional: bool },
+    // Ruta de un archivo, vacio si no se usa
+    Archivo,
+}
+
+struct Campo {
+    etiqueta: &'static str,
+    ayuda: &'static str,
+    tipo: Tipo,
+    valor: String,
+    // Error que se encontro al crear el mundo (por ejemplo un archivo que no se pudo leer)
+    error_al_empezar: Option<String>,
+}
+
+impl Campo {
+    fn numero(etiqueta: &'static str, ayuda: &'static str, valor: &str, minimo: u64, maximo: u64, opcional: bool) -> Self {
+        Self {
+            etiqueta,
+            ayuda,
+            tipo: Tipo::Numero { minimo, maximo, opcional },
+            valor: valor.to_string(),
+            error_al_empezar: None,
+        }
+    }
+
+    // El numero escrito, None si esta vacio y se permite. Los archivos siempre dan None
+    fn leer(&self) -> Result<Option<u64>, String> {
+        let Tipo::Numero { minimo, maximo, opcional } = self.tipo else {
+            return Ok(None);
+        };
+        let texto = self.valor.trim();
+        if texto.is_empty() {
+            return if opcional { Ok(None) } else { Err("Escribe un número".to_string()) };
+        }
+        match texto.parse::<u64>() {
+            Ok(n) if (minimo..=maximo).contains(&n) => Ok(Some(n)),
+            _ => Err(format!("Debe ser un número entre {} y {}", minimo, maximo)),
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        self.leer().err().or_else(|| self.error_al_empezar.clone())
+    }
+
+    fn acepta(&self, ch: char) -> bool {
+        match self.tipo {
+            Tipo::Numero { .. } => ch.is_ascii_digit(),
+            Tipo::Archivo => !ch.is_control(),
+        }
+    }
+}
+
+/// Lo que escogio el usuario en el formulario
+pub enum Salida {
+    // Simulacion nueva, y cuantos dias correr antes de pausar (None es sin limite)
+    Nueva { mundo: Box<Mundo>, dias: Option<u32> },
+    // Seguir la simulacion guardada
+    Cargar,
+}
+
+pub struct Formulario {
+    campos: Vec<Campo>,
+    seleccionado: usize,
+    // Por que no se pudo seguir la simulacion guardada
+    error_guardado: Option<String>,
+}
+
+impl Formulario {
+    pub fn new() -> Self {
+        let campos = vec![
+            Campo::numero("Depredadores", "Empiezan adultos en municipios al azar", "10", 0, 1_000, false),
+            Campo::numero("Conejos iniciales", "", "200", 0, 100_000, false),
+            Campo::numero("Cabras iniciales", "", "50", 0, 100_000, false),
+            Campo::numero("Vacas iniciales", "", "20", 0, 100_000, false),
+            Campo::numero("Semilla", "Vacío: al azar. La misma semilla repite la simulación", "", 0, u64::MAX, true),
+            Campo::numero("Días a simular", "Se pausa al llegar, Espacio sigue. Vacío: sin límite", "365", 1, 100_000, true),
+            Campo {
+                etiqueta: "Archivo de especies",
+                ayuda: "TOML como especies.toml. Vacío: los del programa",
+                tipo: Tipo::Archivo,
+                valor: String::new(),
+                error_al_empezar: None,
+            },
+        ];
+        Self { campos, seleccionado: 0, error_guardado: None }
+    }
+
+    // Dibuja el formulario y atiende el teclado y el mouse de este cuadro.
+    // hay_guardado muestra el boton de seguir la simulacion guardada
+    pub fn actualizar(&mut self, hay_guardado: bool) -> Option<Salida> {
+        draw_text("Configuración de la simulación", 20.0, 45.0, 34.0, BLACK);
+        draw_text(
+            "Tab / Shift+Tab o clic: cambiar de campo | Enter: siguiente campo, en el último empieza",
+            20.0,
+            72.0,
+            20.0,
+            DARKGRAY,
+        );
+
+        for (i, campo) in self.campos.iter().enumerate() {
+            let y = Y_CAMPOS + i as f32 * ALTO_FILA;
+            let seleccionado = i == self.seleccionado;
+            draw_text(campo.etiqueta, 20.0, y + 25.0, 24.0, BLACK);
+            draw_rectangle(X_ENTRADA, y, ANCHO_ENTRADA, 36.0, WHITE);
+            let (grosor, color) = if seleccionado { (3.0, DARKBLUE) } else { (1.0, GRAY) };
+            draw_rectangle_lines(X_ENTRADA, y, ANCHO_ENTRADA, 36.0, grosor, color);
+            // El cursor parpadea medio segundo si y medio no
+            let cursor = if seleccionado && get_time().fract() < 0.5 { "|" } else { "" };
+            draw_text(&format!("{}{}", campo.valor, cursor), X_ENTRADA + 8.0, y + 25.0, 24.0, DARKBLUE);
+            draw_text(campo.ayuda, X_ENTRADA + ANCHO_ENTRADA + 15.0, y + 25.0, 18.0, DARKGRAY);
+            if let Some(error) = campo.error() {
+                draw_text(&error, X_ENTRADA, y + 53.0, 18.0, RED);
+            }
+            if is_mouse_button_pressed(MouseButton::Left)
+                && Rect::new(X_ENTRADA, y, ANCHO_ENTRADA, 36.0).contains(mouse_position().into())
+            {
+                self.seleccionado = i;
+            }
+        }
+
+        let y_botones = Y_CAMPOS + self.campos.len() as f32 * ALTO_FILA + 10.0;
+        let mut empezar = boton(20.0, y_botones, 190.0, 40.0, "Empezar");
+        if let Some(error) = &self.error_guardado {
+            draw_text(error, 20.0, y_botones + 65.0, 18.0, RED);
+        }
+        if hay_guardado && (boton(220.0, y_botones, 380.0, 40.0, "Seguir la simulación guardada (F2)") || is_key_pressed(KeyCode::F2)) {
+            return Some(Salida::Cargar);
+        }
+
+        if is_key_pressed(KeyCode::Tab) {
+            let n = self.campos.len();
+            let atras = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            self.seleccionado = if atras { (self.seleccionado + n - 1) % n } else { (self.seleccionado + 1) % n };
+        }
+        while let Some(ch) = get_char_pressed() {
+            let campo = &mut self.campos[self.seleccionado];
+            match ch {
+                '\r' | '\n' => {
+                    if self.seleccionado + 1 == self.campos.len() {
+                        empezar = true;
+                    } else {
+                        self.seleccionado += 1;
+                    }
+                }
+                '\u{8}' => {
+                    campo.valor.pop();
+                    campo.error_al_empezar = None;
+                }
+                _ if campo.acepta(ch) => {
+                    campo.valor.push(ch);
+                    campo.error_al_empezar = None;
+                }
+                _ => {}
+            }
+        }
+
+        if empezar { self.empezar() } else { None }
+    }
+
+    // Se queda en el formulario mostrando por que no se pudo cargar el archivo guardado
+    pub fn error_al_cargar(&mut self, error: String) {
+        self.error_guardado = Some(error);
+    }
+
+    // Crea el mundo si todos los campos estan bien. Si no, selecciona el primero con error
+    fn empezar(&mut self) -> Option<Salida> {
+        if let Some(i) = self.campos.iter().position(|c| c.leer().is_err()) {
+            self.seleccionado = i;
+            return None;
+        }
+        let ruta = self.campos[ESPECIES].valor.trim();
+        let especies = if ruta.is_empty() {
+            ConfigEspecies::default()
+        } else {
+            match ConfigEspecies::cargar(ruta) {
+                Ok(especies) => especies,
+                Err(e) => {
+                    // Los errores de TOML traen varias lineas con el pedazo del archivo, basta la primera
+                    let error = e.to_string().lines().next().unwrap_or_default().to_string();
+                    self.campos[ESPECIES].error_al_empezar = Some(error);
+                    self.seleccionado = ESPECIES;
+                    return None;
+                }
+            }
+        };
+
+        // Ya se reviso que cada numero este en su rango, y los rangos caben en u32
+        let numero = |i: usize| self.campos[i].leer().ok().flatten();
+        let cantidad = |i: usize| numero(i).unwrap_or(0) as u32;
+        let semilla = numero(SEMILLA).unwrap_or_else(::rand::random);
+        let mut mundo = Mundo::with_config(semilla, especies);
+        mundo.agregar_depredadores(cantidad(DEPREDADORES));
+        mundo.agregar_presas(Especie::Conejo, cantidad(CONEJOS));
+        mundo.agregar_presas(Especie::Cabra, cantidad(CABRAS));
+        mundo.agregar_presas(Especie::Vaca, cantidad(VACAS));
+        let dias = numero(DIAS).map(|d| d as u32);
+        Some(Salida::Nueva { mundo: Box::new(mundo), dias })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campo(valor: &str, opcional: bool) -> Campo {
+        Campo::numero("Prueba", "", valor, 1, 100, opcional)
+    }
+
+    #[test]
+    fn lee_numeros_dentro_del_rango() {
+        assert_eq!(campo("1", false).leer(), Ok(Some(1)));
+        assert_eq!(campo(" 100 ", false).leer(), Ok(Some(100)));
+    }
+
+    #[test]
+    fn rechaza_numeros_fuera_del_rango() {
+        assert!(campo("0", false).leer().is_err());
+        assert!(campo("101", false).leer().is_err());
+        // Mas grande que u64
+        assert!(campo("99999999999999999999999", false).leer().is_err());
+    }
+
+    #[test]
+    fn vacio_solo_si_es_opcional() {
+        assert_eq!(campo("", true).leer(), Ok(None));
+        assert!(campo("", false).leer().is_err());
+    }
+
+    #[test]
+    fn los_archivos_no_son_numeros() {
+        let archivo = Campo {
+            etiqueta: "Archivo",
+            ayuda: "",
+            tipo: Tipo::Archivo,
+            valor: "especies.toml".to_string(),
+            error_al_empezar: None,
+        };
+        assert_eq!(archivo.leer(), Ok(None));
+        assert!(archivo.acepta('a') && !archivo.acepta('\t'));
+        assert!(!campo("", false).acepta('a'));
+    }
+}
@@ -0,0 +1,184 @@
+// Graficas de la interfaz, dibujadas con las primitivas de macroquad
+use macroquad::prelude::*;
+use predator_prey::fase::{escala_redonda, trayectoria, EjePresas};
+use predator_prey::historial::Historial;
+use predator_prey::lotka_volterra::EstadoLv;
+use predator_prey::models::Especie;
+
+pub const COLOR_DEPREDADOR: Color = RED;
+
+// Color de cada especie, el mismo en todas las vistas
+pub fn color_especie(especie: &Especie) -> Color {
+    match especie {
+        Especie::Conejo => ORANGE,
+        Especie::Cabra => DARKGREEN,
+        Especie::Vaca => BLUE,
+    }
+}
+
+// Margenes del area de la grafica dentro de su caja (izquierda, derecha, arriba, abajo)
+const MARGENES: (f32, f32, f32, f32) = (55.0, 55.0, 40.0, 30.0);
+
+// Poblacion de cada especie y de depredadores en todos los dias que han pasado. Las presas
+// van con el eje de la izquierda y los depredadores con el de la derecha, porque casi siempre
+// son muchos menos. Las lineas finas son la prediccion de Lotka-Volterra
+pub fn dibujar_poblaciones(x: f32, y: f32, w: f32, h: f32, historial: &Historial, predicciones: &[EstadoLv]) {
+    draw_rectangle(x, y, w, h, WHITE);
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+    let puntos = &historial.puntos;
+    if puntos.len() < 2 {
+        draw_text("La gráfica aparece después del segundo día", x + 20.0, y + 40.0, 20.0, DARKGRAY);
+        return;
+    }
+
+    let dia_min = puntos[0].dia as f32;
+    let dia_max = puntos[puntos.len() - 1].dia as f32;
+    // Solo las predicciones de los dias que se ven
+    let predicciones: Vec<&EstadoLv> = predicciones
+        .iter()
+        .filter(|p| p.dia as f32 >= dia_min && p.dia as f32 <= dia_max)
+        .collect();
+
+    let max_presas = puntos
+        .iter()
+        .flat_map(|p| p.presas.values().map(|&n| n as f32))
+        .chain(predicciones.iter().flat_map(|p| p.presas.values().map(|&n| n as f32)))
+        .fold(0.0, f32::max);
+    let max_depredadores = puntos
+        .iter()
+        .map(|p| p.depredadores as f32)
+        .chain(predicciones.iter().map(|p| p.depredadores as f32))
+        .fold(0.0, f32::max);
+    let escala_presas = escala_redonda(max_presas);
+    let escala_depredadores = escala_redonda(max_depredadores);
+
+    let (izq, der, arriba, abajo) = MARGENES;
+    let area = Rect::new(x + izq, y + arriba, w - izq - der, h - arriba - abajo);
+    dibujar_ejes(area, (dia_min, dia_max), escala_presas, Some(escala_depredadores));
+
+    let a_pantalla = |dia: f32, valor: f32, escala: f32| {
+        vec2(
+            area.x + (dia - dia_min) / (dia_max - dia_min) * area.w,
+            area.y + area.h - valor / escala * area.h,
+        )
+    };
+
+    for especie in &Especie::TODAS {
+        let color = color_especie(especie);
+        let serie: Vec<Vec2> = puntos
+            .iter()
+            .map(|p| a_pantalla(p.dia as f32, p.presas.get(especie).copied().unwrap_or(0) as f32, escala_presas))
+            .collect();
+        dibujar_linea(&serie, 2.0, color);
+        let prediccion: Vec<Vec2> = predicciones
+            .iter()
+            .map(|p| a_pantalla(p.dia as f32, p.presas.get(especie).copied().unwrap_or(0.0) as f32, escala_presas))
+            .collect();
+        dibujar_linea(&prediccion, 1.0, color);
+    }
+    let serie: Vec<Vec2> = puntos
+        .iter()
+        .map(|p| a_pantalla(p.dia as f32, p.depredadores as f32, escala_depredadores))
+        .collect();
+    dibujar_linea(&serie, 2.0, COLOR_DEPREDADOR);
+    let prediccion: Vec<Vec2> = predicciones
+        .iter()
+        .map(|p| a_pantalla(p.dia as f32, p.depredadores as f32, escala_depredadores))
+        .collect();
+    dibujar_linea(&prediccion, 1.0, COLOR_DEPREDADOR);
+
+    // Leyenda arriba de la grafica
+    let mut leyenda: Vec<(String, Color)> = Especie::TODAS
+        .iter()
+        .map(|e| (format!("{:?}", e), color_especie(e)))
+        .collect();
+    leyenda.push(("Depredadores (eje derecho)".to_string(), COLOR_DEPREDADOR));
+    let mut lx = x + izq;
+    for (nombre, color) in &leyenda {
+        draw_rectangle(lx, y + 12.0, 14.0, 14.0, *color);
+        draw_text(nombre, lx + 18.0, y + 24.0, 18.0, BLACK);
+        lx += 28.0 + measure_text(nombre, None, 18, 1.0).width;
+    }
+    draw_text("(fino: Lotka-Volterra)", lx, y + 24.0, 18.0, DARKGRAY);
+}
+
+// Ejes con el minimo, la mitad y el maximo de cada uno. escala_derecha es el maximo del
+// eje de la derecha, si la grafica tiene dos
+pub fn dibujar_ejes(area: Rect, (x_min, x_max): (f32, f32), escala_izquierda: f32, escala_derecha: Option<f32>) {
+    let base = area.y + area.h;
+    draw_line(area.x, area.y, area.x, base, 1.0, BLACK);
+    draw_line(area.x, base, area.x + area.w, base, 1.0, BLACK);
+    for paso in 0..=2 {
+        let fraccion = paso as f32 / 2.0;
+        let ty = base - fraccion * area.h;
+        draw_line(area.x, ty, area.x + area.w, ty, 1.0, LIGHTGRAY);
+        let texto = numero_corto(escala_izquierda * fraccion);
+        let ancho = measure_text(&texto, None, 16, 1.0).width;
+        draw_text(&texto, area.x - ancho - 5.0, ty + 5.0, 16.0, BLACK);
+        if let Some(escala) = escala_derecha {
+            draw_text(&numero_corto(escala * fraccion), area.x + area.w + 5.0, ty + 5.0, 16.0, COLOR_DEPREDADOR);
+        }
+    }
+    if escala_derecha.is_some() {
+        draw_line(area.x + area.w, area.y, area.x + area.w, base, 1.0, BLACK);
+    }
+    draw_text(&numero_corto(x_min), area.x, base + 18.0, 16.0, BLACK);
+    let texto = numero_corto(x_max);
+    let ancho = measure_text(&texto, None, 16, 1.0).width;
+    draw_text(&texto, area.x + area.w - ancho, base + 18.0, 16.0, BLACK);
+}
+
+// Retrato de fase: depredadores contra presas (cantidad o biomasa), un punto por dia.
+// El primer dia va en verde y el dia actual resaltado en rojo
+pub fn dibujar_fase(x: f32, y: f32, w: f32, h: f32, historial: &Historial, eje: EjePresas) {
+    draw_rectangle(x, y, w, h, WHITE);
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+    let puntos = trayectoria(historial, eje);
+    let (Some(&primero), Some(&ultimo)) = (puntos.first(), puntos.last()) else {
+        draw_text("El retrato de fase aparece después del primer día", x + 20.0, y + 40.0, 20.0, DARKGRAY);
+        return;
+    };
+
+    let escala_x = escala_redonda(puntos.iter().map(|p| p.0).fold(0.0, f32::max));
+    let escala_y = escala_redonda(puntos.iter().map(|p| p.1).fold(0.0, f32::max));
+    let (izq, der, arriba, abajo) = MARGENES;
+    let area = Rect::new(x + izq, y + arriba, w - izq - der, h - arriba - abajo);
+    dibujar_ejes(area, (0.0, escala_x), escala_y, None);
+    let a_pantalla = |(px, py): (f32, f32)| {
+        vec2(area.x + px / escala_x * area.w, area.y + area.h - py / escala_y * area.h)
+    };
+
+    let linea: Vec<Vec2> = puntos.iter().map(|&p| a_pantalla(p)).collect();
+    dibujar_linea(&linea, 1.5, DARKBLUE);
+    let inicio = a_pantalla(primero);
+    draw_circle(inicio.x, inicio.y, 4.0, DARKGREEN);
+    let actual = a_pantalla(ultimo);
+    draw_circle(actual.x, actual.y, 7.0, RED);
+
+    draw_text(
+        &format!("Depredadores contra {} (B: cambiar a {})", eje.titulo().to_lowercase(), eje.otro().titulo().to_lowercase()),
+        x + izq,
+        y + 24.0,
+        18.0,
+        BLACK,
+    );
+}
+
+pub fn dibujar_linea(puntos: &[Vec2], grosor: f32, color: Color) {
+    for par in puntos.windows(2) {
+        draw_line(par[0].x, par[0].y, par[1].x, par[1].y, grosor, color);
+    }
+}
+
+// Numeros de los ejes sin decimales de mas (1500 -> 1.5k)
+pub fn numero_corto(valor: f32) -> String {
+    if valor >= 10000.0 {
+        format!("{:.0}k", valor / 1000.0)
+    } else if valor >= 1000.0 {
+        format!("{:.1}k", valor / 1000.0)
+    } else if valor.fract() == 0.0 {
+        format!("{:.0}", valor)
+    } else {
+        format!("{:.1}", valor)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::estaciones::Estacion;
+use crate::models::{Animal, Especie};
+use crate::reporte::{DiaDepredador, ReporteDia};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Como quedo la poblacion al final de un dia
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PuntoHistorial {
+    pub dia: u32,
+    pub estacion: Estacion,
+    pub presas: BTreeMap<Especie, usize>,
+    pub depredadores: usize,
+    pub biomasa_presas_kg: f32,
+    // Peso promedio de las presas vivas (0 si no queda ninguna)
+    pub peso_medio_kg: f32,
+    pub nacimientos: usize,
+    pub nacimientos_depredadores: usize,
+    // Muertes de presas por causa, y depredadores muertos
+    pub muertes_caza: usize,
+    pub muertes_enfermedad: usize,
+    pub muertes_vejez: usize,
+    pub muertes_hambre: usize,
+    pub muertes_depredadores: usize,
+}
+
+impl PuntoHistorial {
+    pub fn total_presas(&self) -> usize {
+        self.presas.values().sum()
+    }
+}
+
+/// Serie de tiempo de la simulacion, un punto por dia
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Historial {
+    pub puntos: Vec<PuntoHistorial>,
+}
+
+impl Historial {
+    // Agrega el dia que acaba de terminar
+    pub fn registrar(&mut self, reporte: &ReporteDia, presas: &[Animal]) {
+        let biomasa_presas_kg: f32 = presas.iter().map(|p| p.peso_kg).sum();
+        let presas_por_especie = Especie::TODAS
+            .iter()
+            .map(|e| (e.clone(), reporte.totales.get(e).copied().unwrap_or(0)))
+            .collect();
+        self.puntos.push(PuntoHistorial {
+            dia: reporte.dia,
+            estacion: reporte.estacion,
+            presas: presas_por_especie,
+            depredadores: reporte.depredadores.len(),
+            biomasa_presas_kg,
+            peso_medio_kg: if presas.is_empty() { 0.0 } else { biomasa_presas_kg / presas.len() as f32 },
+            nacimientos: reporte.nacimientos.len(),
+            nacimientos_depredadores: reporte.nacimientos_depredadores.len(),
+            muertes_caza: reporte.cazas.len(),
+            muertes_enfermedad: reporte.muertes_enfermedad.len(),
+            muertes_vejez: reporte.muertes_vejez.len(),
+            muertes_hambre: reporte.muertes_hambre.len(),
+            muertes_depredadores: reporte
+                .depredadores_dia
+                .iter()
+                .filter(|d| matches!(d, DiaDepredador::Murio { .. }))
+                .count(),
+        });
+    }
+
+    pub fn ultimo(&self) -> Option<&PuntoHistorial> {
+        self.puntos.last()
+    }
+
+    // Una fila por dia, con encabezado
+    pub fn escribir_csv(&self, salida: &mut impl Write) -> io::Result<()> {
+        write!(salida, "dia,temporada")?;
+        for especie in &Especie::TODAS {
+            let nombre = format!("{:?}", especie).to_lowercase();
+            write!(salida, ",{}", nombre)?;
+        }
+        writeln!(
+            salida,
+            ",depredadores,biomasa_presas_kg,peso_medio_kg,nacimientos,nacimientos_depredadores,\
+             muertes_caza,muertes_enfermedad,muertes_vejez,muertes_hambre,muertes_depredadores"
+        )?;
+        for punto in &self.puntos {
+            write!(salida, "{},{}", punto.dia, punto.estacion)?;
+            for especie in &Especie::TODAS {
+                write!(salida, ",{}", punto.presas.get(especie).copied().unwrap_or(0))?;
+            }
+            writeln!(
+                salida,
+                ",{},{:.2},{:.2},{},{},{},{},{},{},{}",
+                punto.depredadores,
+                punto.biomasa_presas_kg,
+                punto.peso_medio_kg,
+                punto.nacimientos,
+                punto.nacimientos_depredadores,
+                punto.muertes_caza,
+                punto.muertes_enfermedad,
+                punto.muertes_vejez,
+                punto.muertes_hambre,
+                punto.muertes_depredadores
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn guardar_csv(&self, ruta: impl AsRef<Path>) -> io::Result<()> {
+        let mut archivo = BufWriter::new(File::create(ruta)?);
+        self.escribir_csv(&mut archivo)?;
+        archivo.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_con_encabezado_y_un_dia_por_fila() {
+        let punto = PuntoHistorial {
+            dia: 1,
+            estacion: Estacion::Seca,
+            presas: [(Especie::Conejo, 10), (Especie::Cabra, 5), (Especie::Vaca, 2)].into_iter().collect(),
+            depredadores: 3,
+            biomasa_presas_kg: 123.5,
+            peso_medio_kg: 123.5 / 17.0,
+            nacimientos: 1,
+            nacimientos_depredadores: 0,
+            muertes_caza: 2,
+            muertes_enfermedad: 0,
+            muertes_vejez: 1,
+            muertes_hambre: 0,
+            muertes_depredadores: 0,
+        };
+        let historial = Historial { puntos: vec![punto.clone(), PuntoHistorial { dia: 2, ..punto }] };
+        let mut salida = Vec::new();
+        historial.escribir_csv(&mut salida).unwrap();
+        let texto = String::from_utf8(salida).unwrap();
+        let lineas: Vec<&str> = texto.lines().collect();
+
+        assert_eq!(lineas.len(), 3);
+        assert_eq!(
+            lineas[0],
+            "dia,temporada,conejo,cabra,vaca,depredadores,biomasa_presas_kg,peso_medio_kg,nacimientos,\
+             nacimientos_depredadores,muertes_caza,muertes_enfermedad,muertes_vejez,muertes_hambre,muertes_depredadores"
+        );
+        assert_eq!(lineas[1], "1,Temporada seca,10,5,2,3,123.50,7.26,1,0,2,0,1,0,0");
+        assert!(lineas[2].starts_with("2,"));
+    }
+}
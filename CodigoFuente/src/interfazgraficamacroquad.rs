@@ -1,80 +1,271 @@
+use crate::formulario::{Formulario, Salida};
+use crate::graficas::{dibujar_fase, dibujar_poblaciones};
+use crate::mapa_animales::{avanzar_destellos, destellos_del_dia, dibujar_mapa, Destello};
 use macroquad::prelude::*;
-use crate::world::Mundo;
-use crate::models::Especie;
+use predator_prey::fase::EjePresas;
+use predator_prey::lotka_volterra::{EstadoLv, ModeloLotkaVolterra};
+use predator_prey::reporte::ReporteDia;
+use predator_prey::world::Mundo;
+use predator_prey::models::Especie;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Archivo donde se guarda la simulacion con la tecla G
+const ARCHIVO_GUARDADO: &str = "mundo_guardado.json";
+// Archivo donde se exporta la serie de tiempo con la tecla E
+const ARCHIVO_CSV: &str = "historial.csv";
+
+// Segundos que dura un dia a velocidad 1x
+const SEGUNDOS_POR_DIA: f32 = 5.0;
+// Multiplicadores de velocidad que se pueden escoger. Infinito es "lo mas rapido posible"
+const VELOCIDADES: [f32; 8] = [0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 50.0, f32::INFINITY];
+// Tiempo maximo de cada cuadro que se gasta simulando, para que la ventana no se congele
+const PRESUPUESTO_CUADRO: Duration = Duration::from_millis(12);
+
+// Lo que se muestra en el panel grande de la derecha
+#[derive(Clone, Copy, PartialEq)]
+enum Vista {
+    Reporte,
+    Poblaciones,
+    Fase,
+    Mapa,
+}
+
+// Estado de la pantalla de simulacion
+struct Simulacion {
+    mundo: Box<Mundo>,
+    // Prediccion de Lotka-Volterra que arranca con los mismos animales, y la de cada dia
+    modelo: ModeloLotkaVolterra,
+    predicciones: Vec<EstadoLv>,
+    reporte: Vec<String>,
+    // Primera linea del reporte que se ve, el reporte completo no cabe en su caja
+    primera_linea: usize,
+    timer: f32,
+    mensaje: String,
+    vista: Vista,
+    // Cantidad o biomasa de presas en el retrato de fase
+    eje_fase: EjePresas,
+    // Cazas y nacimientos recientes que se resaltan en el mapa
+    destellos: Vec<Destello>,
+    pausado: bool,
+    // Posicion en VELOCIDADES
+    velocidad: usize,
+    // Dia en el que se pausa sola, None si sigue sin limite
+    dia_final: Option<u32>,
+}
+
+impl Simulacion {
+    fn new(mundo: Mundo, mensaje: String) -> Self {
+        let modelo = ModeloLotkaVolterra::desde_mundo(&mundo);
+        Self {
+            mundo: Box::new(mundo),
+            predicciones: vec![modelo.estado()],
+            modelo,
+            reporte: Vec::new(),
+            primera_linea: 0,
+            timer: 0.0,
+            mensaje,
+            vista: Vista::Reporte,
+            eje_fase: EjePresas::Cantidad,
+            destellos: Vec::new(),
+            pausado: false,
+            velocidad: 2,
+            dia_final: None,
+        }
+    }
+
+    fn paso_dia(&mut self) -> ReporteDia {
+        let rep = self.mundo.paso_dia();
+        self.predicciones.push(self.modelo.paso_dia());
+        rep
+    }
+
+    // Muestra el reporte del dia y resalta sus cazas y nacimientos en el mapa
+    fn mostrar(&mut self, rep: &ReporteDia) {
+        self.destellos.extend(destellos_del_dia(rep, &self.mundo));
+        self.reporte.clear();
+        self.reporte.push(rep.to_string());
+    }
+
+    // Avanza los dias que tocan en este cuadro segun la velocidad. Si hay que correr varios
+    // solo se muestra el ultimo, y nunca se pasa de PRESUPUESTO_CUADRO: lo que no alcance se pierde
+    // y la simulacion va mas lenta de lo que dice la velocidad
+    fn correr(&mut self, segundos: f32) {
+        if self.pausado {
+            return;
+        }
+        if self.llego_al_final() {
+            self.pausado = true;
+            self.mensaje = format!("Se llegó al día {}, Espacio sigue sin límite y N un día más", self.mundo.dia_actual);
+            return;
+        }
+        let velocidad = VELOCIDADES[self.velocidad];
+        let inicio = Instant::now();
+        let mut ultimo = None;
+        if velocidad.is_infinite() {
+            while (ultimo.is_none() || inicio.elapsed() < PRESUPUESTO_CUADRO) && !self.llego_al_final() {
+                ultimo = Some(self.paso_dia());
+            }
+        } else {
+            self.timer += segundos * velocidad;
+            while self.timer >= SEGUNDOS_POR_DIA && !self.llego_al_final() {
+                self.timer -= SEGUNDOS_POR_DIA;
+                ultimo = Some(self.paso_dia());
+                if inicio.elapsed() >= PRESUPUESTO_CUADRO {
+                    self.timer = 0.0;
+                }
+            }
+        }
+        if let Some(rep) = ultimo {
+            self.mostrar(&rep);
+        }
+    }
+
+    fn llego_al_final(&self) -> bool {
+        self.dia_final.is_some_and(|dia| self.mundo.dia_actual >= dia)
+    }
+
+    fn aplicar(&mut self, control: Control) {
+        match control {
+            Control::Pausa => {
+                self.pausado = !self.pausado;
+                // Seguir despues de llegar al dia final sigue sin limite
+                if !self.pausado && self.llego_al_final() {
+                    self.dia_final = None;
+                    self.mensaje = format!("Siguiendo después del día {}", self.mundo.dia_actual);
+                }
+            }
+            Control::UnDia => {
+                let rep = self.paso_dia();
+                self.mostrar(&rep);
+                self.timer = 0.0;
+            }
+            Control::MasLento => self.velocidad = self.velocidad.saturating_sub(1),
+            Control::MasRapido => self.velocidad = (self.velocidad + 1).min(VELOCIDADES.len() - 1),
+        }
+    }
+}
+
+// Botones y teclas de la barra de abajo
+#[derive(Clone, Copy)]
+enum Control {
+    Pausa,
+    UnDia,
+    MasLento,
+    MasRapido,
+}
+
+fn texto_velocidad(velocidad: f32) -> String {
+    if velocidad.is_infinite() {
+        "Máxima".to_string()
+    } else {
+        format!("{}x", velocidad)
+    }
+}
 
 // Estados
 enum Estado {
     // El del formulario
-    Formulario { paso: usize, input: String, depredadores: u32, conejos: u32, cabras: u32, vacas: u32 },
+    Formulario(Formulario),
     // El de la simulacion
-    Simulacion { mundo: Mundo, reporte: Vec<String>, timer: f32 },
+    Simulacion(Box<Simulacion>),
 }
 
 pub async fn run_app() {
     // Inicializa el formulario
-    let mut estado = Estado::Formulario { paso: 0, input: String::new(), depredadores: 0, conejos: 0, cabras: 0, vacas: 0 };
+    let mut estado = Estado::Formulario(Formulario::new());
+    let hay_guardado = Path::new(ARCHIVO_GUARDADO).exists();
 
     loop {
         clear_background(LIGHTGRAY);
 
 
         match &mut estado {
-            Estado::Formulario { paso, input, depredadores, conejos, cabras, vacas } => {
-                let preguntas = [
-                    "¿Cuántos depredadores quieres?",
-                    "¿Cuántos conejos iniciales?",
-                    "¿Cuántas cabras iniciales?",
-                    "¿Cuántas vacas iniciales?",
-                ];
-
-                draw_text(&preguntas[*paso], 20.0, 50.0, 30.0, BLACK);
-                draw_text(&format!("> {}", input), 20.0, 100.0, 30.0, DARKBLUE);
-
-                if let Some(ch) = get_char_pressed() {
-                    match ch {
-                        '\r' | '\n' => {
-                            // Vuelve el valor a u32 y lo guarda en su variable correspondiente
-                            let valor: u32 = input.trim().parse().unwrap_or(0);
-                            match *paso {
-                                0 => *depredadores = valor,
-                                1 => *conejos = valor,
-                                2 => *cabras = valor,
-                                // Cuando pasan los 3 pasos (de pedir los valores) seguimos con la simulacion
-                                3 => {
-                                    *vacas = valor;
-                                    let mut mundo = Mundo::new();
-                                    mundo.agregar_depredadores(*depredadores);
-                                    mundo.agregar_presas(Especie::Conejo, *conejos);
-                                    mundo.agregar_presas(Especie::Cabra, *cabras);
-                                    mundo.agregar_presas(Especie::Vaca, *vacas);
-                                    estado = Estado::Simulacion { mundo, reporte: Vec::new(), timer: 0.0 };
-                                    continue;
-                                }
-                                _ => {}
-                            }
-                            *paso += 1;
-                            input.clear();
-                        }
-                        // Eliminan caracteres, solo se aceptan digitos
-                        '\u{8}' => { input.pop(); }
-                        _ if ch.is_ascii_digit() => { input.push(ch); }
-                        _ => {}
+            Estado::Formulario(formulario) => match formulario.actualizar(hay_guardado) {
+                Some(Salida::Nueva { mundo, dias }) => {
+                    let mensaje = format!("Semilla {}", mundo.semilla);
+                    let mut simulacion = Simulacion::new(*mundo, mensaje);
+                    simulacion.dia_final = dias;
+                    estado = Estado::Simulacion(Box::new(simulacion));
+                    continue;
+                }
+                // Seguir la simulacion que se habia guardado
+                Some(Salida::Cargar) => match Mundo::cargar(ARCHIVO_GUARDADO) {
+                    // La ventana no registra estrategias propias, sin ellas esos depredadores no cazarian
+                    Ok(mundo) if !mundo.estrategias_sin_registrar().is_empty() => formulario.error_al_cargar(format!(
+                        "No se pudo cargar {}: usa estrategias propias que este programa no conoce: {}",
+                        ARCHIVO_GUARDADO,
+                        mundo.estrategias_sin_registrar().join(", ")
+                    )),
+                    Ok(mundo) => {
+                        let mensaje = format!("Simulación cargada de {}", ARCHIVO_GUARDADO);
+                        estado = Estado::Simulacion(Box::new(Simulacion::new(mundo, mensaje)));
+                        continue;
                     }
+                    Err(e) => formulario.error_al_cargar(format!("No se pudo cargar {}: {}", ARCHIVO_GUARDADO, e)),
+                },
+                None => {}
+            },
+
+            Estado::Simulacion(simulacion) => {
+                // Pausa, un dia a la vez y velocidad, con el teclado o con los botones de abajo
+                let mut controles = Vec::new();
+                if is_key_pressed(KeyCode::Space) {
+                    controles.push(Control::Pausa);
                 }
-            }
+                if is_key_pressed(KeyCode::N) {
+                    controles.push(Control::UnDia);
+                }
+                if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+                    controles.push(Control::MasLento);
+                }
+                if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+                    controles.push(Control::MasRapido);
+                }
+                controles.extend(dibujar_controles(simulacion.pausado, VELOCIDADES[simulacion.velocidad]));
+                for control in controles {
+                    simulacion.aplicar(control);
+                }
+                simulacion.correr(get_frame_time());
 
-            Estado::Simulacion { mundo, reporte, timer } => {
-                // Control de la duracion del dia
-                *timer += get_frame_time();
-                if *timer >= 5.0 {
-                    *timer = 0.0;
-                    let rep = mundo.paso_dia();
-                    reporte.clear();
-                    reporte.push(rep);
+                let Simulacion { mundo, modelo, predicciones, reporte, primera_linea, mensaje, vista, eje_fase, destellos, .. } = simulacion.as_mut();
+                avanzar_destellos(destellos, get_frame_time());
+
+                // Cambiar lo que se ve en el panel de la derecha
+                if is_key_pressed(KeyCode::Key1) {
+                    *vista = Vista::Reporte;
+                }
+                if is_key_pressed(KeyCode::Key2) {
+                    *vista = Vista::Poblaciones;
+                }
+                if is_key_pressed(KeyCode::Key3) {
+                    *vista = Vista::Fase;
+                }
+                if is_key_pressed(KeyCode::Key4) {
+                    *vista = Vista::Mapa;
+                }
+                if *vista == Vista::Fase && is_key_pressed(KeyCode::B) {
+                    *eje_fase = eje_fase.otro();
+                }
+
+                // Guardar la simulacion para seguirla despues
+                if is_key_pressed(KeyCode::G) {
+                    *mensaje = match mundo.guardar(ARCHIVO_GUARDADO) {
+                        Ok(()) => format!("Simulación guardada en {} (día {})", ARCHIVO_GUARDADO, mundo.dia_actual),
+                        Err(e) => format!("No se pudo guardar: {}", e),
+                    };
+                }
+
+                // Exportar la serie de tiempo de todos los dias
+                if is_key_pressed(KeyCode::E) {
+                    *mensaje = match mundo.historial.guardar_csv(ARCHIVO_CSV) {
+                        Ok(()) => format!("{} días exportados a {}", mundo.historial.puntos.len(), ARCHIVO_CSV),
+                        Err(e) => format!("No se pudo exportar: {}", e),
+                    };
                 }
 
                 // Cajitas de informacion
-                draw_box_with_text(20.0, 20.0, 300.0, 60.0, &format!("Dia actual: {}", mundo.dia_actual));
+                draw_box_with_text(20.0, 20.0, 300.0, 70.0, &format!("Dia actual: {} ({})\nSemilla: {}", mundo.dia_actual, mundo.estacion(), mundo.semilla));
                 draw_box_with_text(20.0, 100.0, 300.0, 100.0, &format!("Depredadores: {}\nPresas: {}", mundo.depredadores.len(), mundo.presas.len()));
 
                 let conejos = mundo.presas.iter().filter(|a| a.especie == Especie::Conejo).count();
@@ -82,15 +273,92 @@ pub async fn run_app() {
                 let vacas = mundo.presas.iter().filter(|a| a.especie == Especie::Vaca).count();
                 draw_box_with_text(20.0, 220.0, 300.0, 120.0, &format!("Conejos: {}\nCabras: {}\nVacas: {}", conejos, cabras, vacas));
 
-                let mut texto_reporte = String::new();
-                if let Some(ultimo) = reporte.last() { texto_reporte = ultimo.clone(); }
-                draw_box_with_text(350.0, 20.0, 800.0, 420.0, &texto_reporte);
+                match vista {
+                    Vista::Reporte => {
+                        let mut texto_reporte = String::new();
+                        if let Some(ultimo) = reporte.last() { texto_reporte = ultimo.clone(); }
+                        // Flechas o rueda del mouse para bajar hasta los municipios
+                        let rueda = mouse_wheel().1;
+                        if is_key_pressed(KeyCode::Down) || rueda < 0.0 {
+                            *primera_linea += 3;
+                        }
+                        if is_key_pressed(KeyCode::Up) || rueda > 0.0 {
+                            *primera_linea = primera_linea.saturating_sub(3);
+                        }
+                        *primera_linea = draw_box_with_scroll(350.0, 20.0, 800.0, 560.0, &texto_reporte, *primera_linea);
+                    }
+                    Vista::Poblaciones => dibujar_poblaciones(350.0, 20.0, 800.0, 560.0, &mundo.historial, predicciones),
+                    Vista::Fase => dibujar_fase(350.0, 20.0, 800.0, 560.0, &mundo.historial, *eje_fase),
+                    Vista::Mapa => dibujar_mapa(350.0, 20.0, 800.0, 560.0, mundo, destellos),
+                }
+                draw_box_with_text(20.0, 360.0, 300.0, 80.0, &format!("1: reporte | 2: gráfica | 3: fase\n4: mapa | G: guardar | E: CSV\n{}", mensaje));
+
+                // Lo que predicen las ecuaciones, para compararlo con los conteos de arriba
+                let prediccion = modelo.estado();
+                let mut texto_modelo = String::from("Lotka-Volterra:");
+                for (especie, n) in &prediccion.presas {
+                    texto_modelo.push_str(&format!("\n{:?}: {:.1}", especie, n));
+                }
+                texto_modelo.push_str(&format!("\nDepredadores: {:.1}", prediccion.depredadores));
+                draw_box_with_text(20.0, 450.0, 300.0, 130.0, &texto_modelo);
             }
         }
 
         next_frame().await;
     }
 }
+// Barra de botones debajo de las cajas. Devuelve los botones a los que les dieron clic
+fn dibujar_controles(pausado: bool, velocidad: f32) -> Vec<Control> {
+    let mut controles = Vec::new();
+    let y = 595.0;
+    if boton(20.0, y, 190.0, 40.0, if pausado { "Seguir (Espacio)" } else { "Pausa (Espacio)" }) {
+        controles.push(Control::Pausa);
+    }
+    if boton(220.0, y, 130.0, 40.0, "Un día (N)") {
+        controles.push(Control::UnDia);
+    }
+    if boton(360.0, y, 150.0, 40.0, "Más lento (-)") {
+        controles.push(Control::MasLento);
+    }
+    let estado = if pausado { " (en pausa)" } else { "" };
+    draw_box_with_text(520.0, y, 270.0, 40.0, &format!("Velocidad: {}{}", texto_velocidad(velocidad), estado));
+    if boton(800.0, y, 160.0, 40.0, "Más rápido (+)") {
+        controles.push(Control::MasRapido);
+    }
+    controles
+}
+
+// Caja con texto que se resalta con el mouse encima. Devuelve true si le dieron clic
+pub fn boton(x: f32, y: f32, w: f32, h: f32, texto: &str) -> bool {
+    let encima = Rect::new(x, y, w, h).contains(mouse_position().into());
+    draw_rectangle(x, y, w, h, if encima { SKYBLUE } else { WHITE });
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+    draw_text(texto, x + 10.0, y + 25.0, 20.0, BLACK);
+    encima && is_mouse_button_pressed(MouseButton::Left)
+}
+
+// Caja con un texto mas largo de lo que cabe: muestra las lineas desde primera_linea y abajo
+// dice cuales se ven. Devuelve primera_linea ajustada para no pasarse del final
+fn draw_box_with_scroll(x: f32, y: f32, w: f32, h: f32, text: &str, primera_linea: usize) -> usize {
+    draw_rectangle(x, y, w, h, WHITE);
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+    let lineas: Vec<&str> = text.lines().collect();
+    // Una linea menos para el aviso de abajo
+    let caben = ((h - 10.0) / 25.0) as usize - 1;
+    let primera = primera_linea.min(lineas.len().saturating_sub(caben));
+    let mut y_text = y + 25.0;
+    for line in lineas.iter().skip(primera).take(caben) {
+        draw_text(line, x + 10.0, y_text, 20.0, BLACK);
+        y_text += 25.0;
+    }
+    if lineas.len() > caben {
+        let ultima = (primera + caben).min(lineas.len());
+        let aviso = format!("Líneas {}-{} de {} (flechas o rueda del mouse)", primera + 1, ultima, lineas.len());
+        draw_text(&aviso, x + 10.0, y + h - 10.0, 18.0, DARKGRAY);
+    }
+    primera
+}
+
 // Crear caja
 fn draw_box_with_text(x: f32, y: f32, w: f32, h: f32, text: &str) {
     draw_rectangle(x, y, w, h, WHITE);
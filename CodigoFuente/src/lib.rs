@@ -0,0 +1,16 @@
+// Nucleo de la simulacion, lo comparten la interfaz grafica y el simulador por consola
+pub mod apareamiento;
+pub mod enfermedad;
+pub mod especies;
+pub mod estaciones;
+pub mod estrategia;
+pub mod eventos;
+pub mod fase;
+pub mod historial;
+pub mod lotka_volterra;
+pub mod mapa;
+pub mod models;
+pub mod persistencia;
+pub mod region;
+pub mod reporte;
+pub mod world;
@@ -0,0 +1,289 @@
+// Modo determinista: el mismo valle resuelto con ecuaciones de Lotka-Volterra en vez de
+// animal por animal. Sirve para comparar la prediccion de campo medio con la simulacion
+//
+//   dN_i/dt = b_i(t) q N_i (1 - B/K) - N_i/edad_maxima_i - f_i P
+//   dP/dt   = e P max(I - m, 0) - s P max(1 - I/m, 0) - P/edad_maxima
+//   f_i     = a_i N_i / (1 + suma de a_j N_j h_j)
+//
+// N_i son las presas de cada especie, P los depredadores, B = suma de w_i N_i la biomasa de
+// presas, K la capacidad de carga de todo el valle y q la calidad de habitat promedio. f_i es la respuesta funcional tipo II
+// (Holling): un depredador no caza mientras se come su presa, que le dura h_j dias, e
+// I = suma de f_i w_i c_i son los kg de carne que come al dia. Con mas de m (nivel_minimo_diario)
+// nacen crias; con menos se mueren de hambre, todos en unos s = 1/DIAS_MAX_SIN_COMER dias si no
+// comen nada, como en Depredador::esta_vivo. Se integra con Runge-Kutta de orden 4
+use crate::especies::{ConfigEspecies, ParametrosDepredador, ParametrosEspecie};
+use crate::estaciones::Estacion;
+use crate::models::{ComportamientoAnimal, Especie, DIAS_MAX_SIN_COMER};
+use crate::world::Mundo;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
+
+// Pasos de Runge-Kutta por cada dia, con uno solo las tasas altas de los conejos se disparan
+const PASOS_POR_DIA: u32 = 10;
+
+/// Tasas de una especie de presa en las ecuaciones (por dia)
+#[derive(Debug, Clone)]
+pub struct PresaLv {
+    pub especie: Especie,
+    // Crias por individuo por dia con habitat vacio, antes de la temporada
+    pub natalidad: f64,
+    // Muertes por vejez por individuo por dia
+    pub mortalidad: f64,
+    // Fraccion de las presas que caza cada depredador por dia si no pierde tiempo comiendo
+    pub tasa_ataque: f64,
+    // Peso de un adulto y la parte que es carne
+    pub peso_kg: f64,
+    pub fraccion_comestible: f64,
+    // Dias que el depredador pasa comiendose una presa (sin cazar otra)
+    pub dias_manejo: f64,
+}
+
+impl PresaLv {
+    // Las tasas salen de los mismos parametros que usa el modelo de agentes
+    fn desde_parametros(especie: Especie, p: &ParametrosEspecie, d: &ParametrosDepredador, area_valle_m2: f64) -> Self {
+        // Una hembra espera 1/tasa dias para quedar preñada y luego pasa la gestacion y el posparto.
+        // La mitad de la poblacion son hembras y las camadas son de 1 a max_crias_por_parto
+        let tasa = p.tasa_reproduccion_diaria() as f64;
+        let ciclo_dias = if tasa > 0.0 {
+            1.0 / tasa + (p.dias_gestacion + p.dias_posparto) as f64
+        } else {
+            f64::INFINITY
+        };
+        let crias_por_parto = (1.0 + p.max_crias_por_parto() as f64) / 2.0;
+        // Probabilidad de que una presa este a su alcance, por el exito de un ataque
+        let alcance = PI * (d.radio_caza as f64).powi(2) / area_valle_m2;
+        let carne_kg = (p.crecimiento.a * p.fraccion_comestible) as f64;
+        Self {
+            especie,
+            natalidad: 0.5 * crias_por_parto / ciclo_dias,
+            mortalidad: 1.0 / p.edad_maxima() as f64,
+            tasa_ataque: alcance.min(1.0) * p.defensa.exito_base as f64,
+            peso_kg: p.crecimiento.a as f64,
+            fraccion_comestible: p.fraccion_comestible as f64,
+            dias_manejo: (carne_kg / d.capacidad_estomago_kg as f64).max(1.0),
+        }
+    }
+}
+
+/// Lo que predicen las ecuaciones para un dia
+#[derive(Debug, Clone, Default)]
+pub struct EstadoLv {
+    pub dia: u32,
+    pub presas: BTreeMap<Especie, f64>,
+    pub depredadores: f64,
+}
+
+impl EstadoLv {
+    pub fn total_presas(&self) -> f64 {
+        self.presas.values().sum()
+    }
+}
+
+impl fmt::Display for EstadoLv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Lotka-Volterra día {}:", self.dia)?;
+        for (especie, n) in &self.presas {
+            write!(f, " {:?} {:.1} |", especie, n)?;
+        }
+        write!(f, " Depredadores {:.2}", self.depredadores)
+    }
+}
+
+/// Las ecuaciones de Lotka-Volterra del valle con su estado actual
+#[derive(Debug, Clone)]
+pub struct ModeloLotkaVolterra {
+    pub presas: Vec<PresaLv>,
+    // Capacidad de carga de todo el valle (kg de presas) y calidad de habitat promedio,
+    // pesada por la capacidad de cada municipio
+    pub capacidad_carga_kg: f64,
+    pub calidad_habitat: f64,
+    // Crias de depredador por kg de carne comido por encima del nivel minimo
+    pub eficiencia: f64,
+    pub nivel_minimo_diario: f64,
+    // Fraccion de los depredadores que se mueren de hambre por dia si no comen nada
+    pub muerte_hambre: f64,
+    pub mortalidad_depredador: f64,
+    // Factor de reproduccion de cada temporada
+    pub reproduccion_estacion: [f64; 4],
+    pub dia: u32,
+    // Presas de cada especie (en el orden de `presas`) y depredadores
+    pub n: Vec<f64>,
+    pub p: f64,
+}
+
+impl ModeloLotkaVolterra {
+    // Arranca desde el estado actual de un mundo, con sus especies, su region y su mapa
+    pub fn desde_mundo(mundo: &Mundo) -> Self {
+        let especies = &mundo.especies;
+        let area_valle_m2 = (mundo.mapa.ancho * mundo.mapa.alto) as f64 * mundo.region.parches.len() as f64;
+        let capacidad_carga_kg: f64 = mundo.region.parches.iter().map(|p| p.capacidad_carga_kg as f64).sum();
+        let mut modelo = Self::new(especies, capacidad_carga_kg, area_valle_m2);
+        modelo.calidad_habitat = mundo
+            .region
+            .parches
+            .iter()
+            .map(|p| (p.calidad_habitat * p.capacidad_carga_kg) as f64)
+            .sum::<f64>()
+            / capacidad_carga_kg;
+        modelo.dia = mundo.dia_actual;
+        for (i, presa) in modelo.presas.iter().enumerate() {
+            modelo.n[i] = mundo.presas.iter().filter(|a| a.especie == presa.especie).count() as f64;
+        }
+        modelo.p = mundo.depredadores.len() as f64;
+        modelo
+    }
+
+    // Modelo vacio (sin animales) con las tasas de una configuracion de especies y habitat perfecto
+    pub fn new(especies: &ConfigEspecies, capacidad_carga_kg: f64, area_valle_m2: f64) -> Self {
+        let d = &especies.depredador;
+        let presas: Vec<PresaLv> = Especie::TODAS
+            .into_iter()
+            .map(|e| {
+                let parametros = especies.de(&e);
+                PresaLv::desde_parametros(e, parametros, d, area_valle_m2)
+            })
+            .collect();
+        // Con una reserva optima una hembra (la mitad) tiene camadas a la tasa completa
+        let crias_por_parto = (1.0 + d.max_crias_por_parto as f64) / 2.0;
+        let eficiencia = 0.5 * d.tasa_reproduccion_diaria as f64 * crias_por_parto / d.nivel_optimo_diario as f64;
+        let estaciones = [Estacion::Seca, Estacion::PrimerasLluvias, Estacion::Veranillo, Estacion::SegundasLluvias]
+            .map(|e| especies.estaciones.de(e).reproduccion as f64);
+        let cantidad = presas.len();
+        Self {
+            presas,
+            capacidad_carga_kg,
+            calidad_habitat: 1.0,
+            eficiencia,
+            nivel_minimo_diario: d.nivel_minimo_diario as f64,
+            muerte_hambre: 1.0 / DIAS_MAX_SIN_COMER as f64,
+            mortalidad_depredador: 1.0 / d.edad_maxima as f64,
+            reproduccion_estacion: estaciones,
+            dia: 0,
+            n: vec![0.0; cantidad],
+            p: 0.0,
+        }
+    }
+
+    // Avanza un dia, igual que Mundo::paso_dia
+    pub fn paso_dia(&mut self) -> EstadoLv {
+        self.dia += 1;
+        let factor = self.reproduccion_estacion[Estacion::del_dia(self.dia) as usize];
+        let mut y = self.n.clone();
+        y.push(self.p);
+        let h = 1.0 / PASOS_POR_DIA as f64;
+        for _ in 0..PASOS_POR_DIA {
+            y = rk4(&y, h, |y| self.derivadas(y, factor));
+        }
+        // Las ecuaciones no bajan de cero, pero el redondeo numerico si puede
+        self.p = y.pop().unwrap_or(0.0).max(0.0);
+        self.n = y.into_iter().map(|n| n.max(0.0)).collect();
+        self.estado()
+    }
+
+    pub fn estado(&self) -> EstadoLv {
+        EstadoLv {
+            dia: self.dia,
+            presas: self.presas.iter().map(|p| p.especie.clone()).zip(self.n.iter().copied()).collect(),
+            depredadores: self.p,
+        }
+    }
+
+    // Lado derecho de las ecuaciones. y tiene las presas de cada especie y al final los depredadores
+    fn derivadas(&self, y: &[f64], factor_estacion: f64) -> Vec<f64> {
+        let p = y[self.presas.len()];
+        let biomasa: f64 = self.presas.iter().zip(y).map(|(presa, n)| presa.peso_kg * n).sum();
+        let espacio = self.calidad_habitat * (1.0 - biomasa / self.capacidad_carga_kg).max(0.0);
+        // Tiempo ocupado comiendo (denominador de la respuesta tipo II)
+        let ocupado: f64 = 1.0 + self.presas.iter().zip(y).map(|(presa, n)| presa.tasa_ataque * n * presa.dias_manejo).sum::<f64>();
+        let mut dy = Vec::with_capacity(y.len());
+        let mut carne_por_depredador = 0.0;
+        for (presa, &n) in self.presas.iter().zip(y) {
+            let cazadas_por_depredador = presa.tasa_ataque * n / ocupado;
+            carne_por_depredador += cazadas_por_depredador * presa.peso_kg * presa.fraccion_comestible;
+            dy.push(presa.natalidad * factor_estacion * n * espacio - presa.mortalidad * n - cazadas_por_depredador * p);
+        }
+        let sobra = (carne_por_depredador - self.nivel_minimo_diario).max(0.0);
+        let falta = (1.0 - carne_por_depredador / self.nivel_minimo_diario).max(0.0);
+        dy.push(self.eficiencia * p * sobra - self.muerte_hambre * p * falta - self.mortalidad_depredador * p);
+        dy
+    }
+}
+
+// Un paso de Runge-Kutta de orden 4 de tamaño h
+fn rk4(y: &[f64], h: f64, f: impl Fn(&[f64]) -> Vec<f64>) -> Vec<f64> {
+    let mas = |a: &[f64], k: &[f64], escala: f64| -> Vec<f64> { a.iter().zip(k).map(|(a, k)| a + escala * k).collect() };
+    let k1 = f(y);
+    let k2 = f(&mas(y, &k1, h / 2.0));
+    let k3 = f(&mas(y, &k2, h / 2.0));
+    let k4 = f(&mas(y, &k3, h));
+    (0..y.len())
+        .map(|i| y[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rk4_sigue_una_exponencial() {
+        // dy/dt = -0.3 y, con y(0) = 2, hasta t = 5
+        let tasa = -0.3;
+        let h = 0.1;
+        let mut y = vec![2.0];
+        for _ in 0..50 {
+            y = rk4(&y, h, |y| vec![tasa * y[0]]);
+        }
+        let exacto = 2.0 * (tasa * 5.0_f64).exp();
+        assert!((y[0] - exacto).abs() < 1e-7, "rk4 dio {} y el valor exacto es {}", y[0], exacto);
+    }
+
+    #[test]
+    fn solo_presas_llegan_a_la_capacidad_de_carga() {
+        let capacidad = 1.0e5;
+        let mut modelo = ModeloLotkaVolterra::new(&ConfigEspecies::default(), capacidad, 1.0e7);
+        let conejo = modelo.presas[0].clone();
+        assert_eq!(conejo.especie, Especie::Conejo);
+        modelo.n[0] = 10.0;
+        // Sin temporadas (factor 1) la logistica para donde las crias reponen a los que se mueren de viejos
+        let mut y = modelo.n.clone();
+        y.push(0.0);
+        for _ in 0..3000 * PASOS_POR_DIA {
+            y = rk4(&y, 1.0 / PASOS_POR_DIA as f64, |y| modelo.derivadas(y, 1.0));
+        }
+        let biomasa = y[0] * conejo.peso_kg;
+        let esperada = capacidad * (1.0 - conejo.mortalidad / conejo.natalidad);
+        assert!((biomasa - esperada).abs() < 1e-3 * capacidad, "biomasa {} y se esperaba {}", biomasa, esperada);
+        assert!(biomasa > 0.95 * capacidad && biomasa <= capacidad);
+    }
+
+    #[test]
+    fn sin_presas_los_depredadores_se_mueren_de_hambre() {
+        let mut modelo = ModeloLotkaVolterra::new(&ConfigEspecies::default(), 1.0e5, 1.0e7);
+        let mut y = vec![0.0; modelo.presas.len()];
+        y.push(100.0);
+        let dp = modelo.derivadas(&y, 1.0)[modelo.presas.len()];
+        let esperada = -100.0 * (1.0 / DIAS_MAX_SIN_COMER as f64 + modelo.mortalidad_depredador);
+        assert!((dp - esperada).abs() < 1e-9, "dP/dt {} y se esperaba {}", dp, esperada);
+
+        // En los agentes ninguno pasa de DIAS_MAX_SIN_COMER dias sin comer; aqui en 30 dias
+        // quedan menos del 1%
+        modelo.p = 100.0;
+        for _ in 0..30 {
+            modelo.paso_dia();
+        }
+        assert!(modelo.p < 1.0, "quedan {} depredadores", modelo.p);
+    }
+
+    #[test]
+    fn sin_animales_se_queda_en_cero() {
+        let mut modelo = ModeloLotkaVolterra::new(&ConfigEspecies::default(), 1.0e6, 1.0e6);
+        for _ in 0..30 {
+            let estado = modelo.paso_dia();
+            assert_eq!(estado.total_presas(), 0.0);
+            assert_eq!(estado.depredadores, 0.0);
+        }
+        assert_eq!(modelo.dia, 30);
+    }
+}
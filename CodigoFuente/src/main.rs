@@ -1,8 +1,21 @@
-mod models;
-mod world;
+mod formulario;
+mod graficas;
 mod interfazgraficamacroquad;
+mod mapa_animales;
 
-#[macroquad::main("Simulación Ecosistema")]
+use macroquad::window::Conf;
+
+// Ventana con espacio para las cajas de la izquierda, la vista de la derecha y los controles de abajo
+fn configuracion() -> Conf {
+    Conf {
+        window_title: "Simulación Ecosistema".to_string(),
+        window_width: 1180,
+        window_height: 650,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(configuracion)]
 async fn main() {
     interfazgraficamacroquad::run_app().await;
 }
@@ -0,0 +1,74 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Lugar de un animal en el mapa, en metros
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Posicion {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Posicion {
+    pub fn distancia(&self, otra: &Posicion) -> f32 {
+        ((self.x - otra.x).powi(2) + (self.y - otra.y).powi(2)).sqrt()
+    }
+}
+
+/// Terreno continuo donde viven los animales, de ancho x alto metros
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mapa {
+    pub ancho: f32,
+    pub alto: f32,
+}
+
+impl Default for Mapa {
+    fn default() -> Self {
+        Self { ancho: 1000.0, alto: 1000.0 }
+    }
+}
+
+impl Mapa {
+    // El ancho y el alto tienen que ser numeros finitos mayores que 0, si no no se puede
+    // escoger un punto al azar adentro
+    pub fn new(ancho: f32, alto: f32) -> Result<Self, String> {
+        if !(ancho.is_finite() && alto.is_finite() && ancho > 0.0 && alto > 0.0) {
+            return Err(format!("el mapa debe tener ancho y alto finitos y mayores que 0, no {}x{}", ancho, alto));
+        }
+        Ok(Self { ancho, alto })
+    }
+
+    // Un punto cualquiera del mapa
+    pub fn posicion_al_azar<R: Rng>(&self, rng: &mut R) -> Posicion {
+        Posicion {
+            x: rng.gen_range(0.0..=self.ancho),
+            y: rng.gen_range(0.0..=self.alto),
+        }
+    }
+
+    // Camina en una direccion al azar hasta distancia_max metros, sin salirse del mapa
+    pub fn mover_al_azar<R: Rng>(&self, desde: Posicion, distancia_max: f32, rng: &mut R) -> Posicion {
+        if distancia_max <= 0.0 {
+            return desde;
+        }
+        let angulo = rng.gen_range(0.0..TAU);
+        let distancia = rng.gen_range(0.0..=distancia_max);
+        Posicion {
+            x: (desde.x + distancia * angulo.cos()).clamp(0.0, self.ancho),
+            y: (desde.y + distancia * angulo.sin()).clamp(0.0, self.alto),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rechaza_tamanos_invalidos() {
+        assert!(Mapa::new(2000.0, 500.0).is_ok());
+        for (ancho, alto) in [(0.0, 100.0), (100.0, -1.0), (f32::INFINITY, f32::INFINITY), (f32::NAN, 100.0)] {
+            assert!(Mapa::new(ancho, alto).is_err(), "se acepto {}x{}", ancho, alto);
+        }
+    }
+}
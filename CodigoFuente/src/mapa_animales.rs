@@ -0,0 +1,149 @@
+// Vista del valle con cada animal dibujado en su lugar, un cuadro por municipio
+use crate::graficas::{color_especie, COLOR_DEPREDADOR};
+use macroquad::prelude::*;
+use predator_prey::mapa::Posicion;
+use predator_prey::models::{EstadoAlimentacion, Especie};
+use predator_prey::region::Municipio;
+use predator_prey::reporte::ReporteDia;
+use predator_prey::world::Mundo;
+
+// Segundos que dura resaltada una caza o un nacimiento
+const DURACION_DESTELLO: f32 = 2.0;
+// Municipios por fila, de norte a sur
+const COLUMNAS: usize = 5;
+// Alto reservado abajo para la leyenda
+const ALTO_LEYENDA: f32 = 40.0;
+
+/// Algo que paso en el dia y que se resalta un momento en el mapa
+pub struct Destello {
+    pub municipio: Municipio,
+    pub posicion: Posicion,
+    pub color: Color,
+    // Segundos que le quedan en pantalla
+    pub restante: f32,
+}
+
+// Cazas (donde quedo el depredador) y nacimientos (donde esta la cria) del dia que acaba de pasar
+pub fn destellos_del_dia(reporte: &ReporteDia, mundo: &Mundo) -> Vec<Destello> {
+    let mut destellos = Vec::new();
+    let mut agregar = |municipio, posicion, color| {
+        destellos.push(Destello { municipio, posicion, color, restante: DURACION_DESTELLO });
+    };
+    for caza in &reporte.cazas {
+        if let Some(d) = mundo.depredadores.iter().find(|d| d.id == caza.depredador_id) {
+            agregar(d.municipio, d.posicion, COLOR_DEPREDADOR);
+        }
+    }
+    for nacimiento in &reporte.nacimientos {
+        if let Some(cria) = mundo.presas.iter().find(|a| a.id == nacimiento.cria_id) {
+            agregar(cria.municipio, cria.posicion, color_especie(&nacimiento.especie));
+        }
+    }
+    for nacimiento in &reporte.nacimientos_depredadores {
+        if let Some(cria) = mundo.depredadores.iter().find(|d| d.id == nacimiento.cria_id) {
+            agregar(cria.municipio, cria.posicion, BLACK);
+        }
+    }
+    destellos
+}
+
+// Descuenta el tiempo del cuadro y quita los que ya se acabaron
+pub fn avanzar_destellos(destellos: &mut Vec<Destello>, segundos: f32) {
+    for destello in destellos.iter_mut() {
+        destello.restante -= segundos;
+    }
+    destellos.retain(|d| d.restante > 0.0);
+}
+
+// Color de un depredador segun que tan bien comido esta
+pub fn color_alimentacion(estado: EstadoAlimentacion) -> Color {
+    match estado {
+        EstadoAlimentacion::Optimo => PURPLE,
+        EstadoAlimentacion::Minimo => GOLD,
+        EstadoAlimentacion::Hambriento => RED,
+    }
+}
+
+// Radio del circulo de una presa: crece con la raiz del peso para que un conejo
+// se alcance a ver al lado de una vaca
+fn radio_presa(peso_kg: f32) -> f32 {
+    (1.0 + peso_kg.max(0.0).sqrt() / 4.0).clamp(1.5, 6.0)
+}
+
+// Los diez municipios en dos filas. El fondo es mas verde mientras mas forraje tenga el municipio,
+// las presas son circulos del color de su especie y los depredadores cuadros del color de su hambre
+pub fn dibujar_mapa(x: f32, y: f32, w: f32, h: f32, mundo: &Mundo, destellos: &[Destello]) {
+    draw_rectangle(x, y, w, h, WHITE);
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+
+    let filas = Municipio::TODOS.len().div_ceil(COLUMNAS);
+    let margen = 10.0;
+    let ancho_cuadro = (w - margen * (COLUMNAS as f32 + 1.0)) / COLUMNAS as f32;
+    let alto_cuadro = (h - ALTO_LEYENDA - margen * (filas as f32 + 1.0)) / filas as f32;
+    // El mapa de cada municipio no se estira, se deja con su proporcion
+    let escala = (ancho_cuadro / mundo.mapa.ancho).min((alto_cuadro - 20.0) / mundo.mapa.alto);
+    let cuadro = |municipio: Municipio| {
+        let i = municipio.indice();
+        let cx = x + margen + (i % COLUMNAS) as f32 * (ancho_cuadro + margen);
+        let cy = y + margen + (i / COLUMNAS) as f32 * (alto_cuadro + margen);
+        Rect::new(cx, cy + 20.0, mundo.mapa.ancho * escala, mundo.mapa.alto * escala)
+    };
+    let a_pantalla = |municipio: Municipio, posicion: &Posicion| {
+        let r = cuadro(municipio);
+        vec2(r.x + posicion.x * escala, r.y + posicion.y * escala)
+    };
+
+    for parche in &mundo.region.parches {
+        let r = cuadro(parche.municipio);
+        let forraje = (parche.forraje_kg / parche.capacidad_forraje_kg).clamp(0.0, 1.0);
+        draw_rectangle(r.x, r.y, r.w, r.h, Color::new(0.55, 0.75, 0.45, 0.15 + 0.5 * forraje));
+        draw_rectangle_lines(r.x, r.y, r.w, r.h, 1.0, DARKGRAY);
+        draw_text(&parche.municipio.to_string(), r.x, r.y - 5.0, 18.0, BLACK);
+    }
+
+    // Primero las vacas y de ultimo los conejos, para que los grandes no tapen a los pequeños
+    for especie in Especie::TODAS.iter().rev() {
+        let color = color_especie(especie);
+        for presa in mundo.presas.iter().filter(|a| &a.especie == especie) {
+            let p = a_pantalla(presa.municipio, &presa.posicion);
+            draw_circle(p.x, p.y, radio_presa(presa.peso_kg), color);
+        }
+    }
+    for depredador in &mundo.depredadores {
+        let p = a_pantalla(depredador.municipio, &depredador.posicion);
+        let color = color_alimentacion(depredador.estado_alimentacion());
+        draw_rectangle(p.x - 4.0, p.y - 4.0, 8.0, 8.0, color);
+        draw_rectangle_lines(p.x - 4.0, p.y - 4.0, 8.0, 8.0, 1.5, BLACK);
+    }
+
+    // Anillo que se abre y se desvanece
+    for destello in destellos {
+        let p = a_pantalla(destello.municipio, &destello.posicion);
+        let avance = 1.0 - destello.restante / DURACION_DESTELLO;
+        let color = Color { a: 1.0 - avance, ..destello.color };
+        draw_circle_lines(p.x, p.y, 6.0 + 14.0 * avance, 2.0, color);
+    }
+
+    dibujar_leyenda(x + margen, y + h - ALTO_LEYENDA + 25.0);
+}
+
+fn dibujar_leyenda(x: f32, y: f32) {
+    let mut lx = x;
+    for especie in &Especie::TODAS {
+        let nombre = format!("{:?}", especie);
+        draw_circle(lx + 6.0, y - 6.0, 5.0, color_especie(especie));
+        draw_text(&nombre, lx + 16.0, y, 18.0, BLACK);
+        lx += 26.0 + measure_text(&nombre, None, 18, 1.0).width;
+    }
+    draw_text("Depredadores:", lx, y, 18.0, BLACK);
+    lx += 10.0 + measure_text("Depredadores:", None, 18, 1.0).width;
+    for estado in [EstadoAlimentacion::Optimo, EstadoAlimentacion::Minimo, EstadoAlimentacion::Hambriento] {
+        let nombre = estado.to_string();
+        draw_rectangle(lx, y - 11.0, 10.0, 10.0, color_alimentacion(estado));
+        draw_rectangle_lines(lx, y - 11.0, 10.0, 10.0, 1.5, BLACK);
+        draw_text(&nombre, lx + 16.0, y, 18.0, BLACK);
+        lx += 26.0 + measure_text(&nombre, None, 18, 1.0).width;
+    }
+    draw_circle_lines(lx + 6.0, y - 6.0, 6.0, 2.0, DARKGRAY);
+    draw_text("caza o nacimiento", lx + 16.0, y, 18.0, DARKGRAY);
+}
@@ -1,69 +1,165 @@
+use crate::enfermedad::{EstadoSalud, FinInfeccion, ParametrosEnfermedad};
+use crate::especies::{ParametrosDepredador, ParametrosEspecie};
+use crate::estrategia::Estrategia;
+use crate::mapa::Posicion;
+use crate::region::Municipio;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Aqui definimos las especies de presas que tendremos
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Especie {
     Conejo,
     Cabra,
     Vaca,
 }
+
+impl Especie {
+    pub const TODAS: [Especie; 3] = [Especie::Conejo, Especie::Cabra, Especie::Vaca];
+}
+
 // Si son machos o hembras
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Sexo {
     Macho,
     Hembra,
 }
 
-// Crecimiento gomperzt
-pub type GrowthFunction = Box<dyn Fn(u32) -> f32 + Send + Sync>;
-
-// funcion que usamos con gomperzt para ver el peso segun la edad del animlal
-pub fn create_growth_function(species: Especie) -> GrowthFunction {
-    let (a, b, k) = match species {
-        // Peso, velocidad de creciemiento, curva de tiempo
-        Especie::Conejo => (5.0, 2.5, 0.05),
-        Especie::Cabra => (75.0, 2.8, 0.01),
-        Especie::Vaca => (700.0, 3.0, 0.008),
-    };
+// Crecimiento gomperzt. Guardamos los parametros y no un closure para poder
+// clonar, guardar y cargar el animal sin perder su curva de crecimiento
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Gompertz {
+    pub a: f32, // Peso
+    pub b: f32, // Velocidad de creciemiento
+    pub k: f32, // Curva de tiempo
+}
 
-    Box::new(move |age_days| {
+impl Gompertz {
+    // Peso segun la edad del animal
+    pub fn peso(&self, age_days: u32) -> f32 {
         let t = age_days as f32;
-        a * (-b * (-k * t).exp()).exp()
-    })
+        self.a * (-self.b * (-self.k * t).exp()).exp()
+    }
+}
+
+/// En que parte del ciclo reproductivo esta una hembra
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum EstadoReproductivo {
+    #[default]
+    Disponible,
+    // Preñada, pare cuando dias_restantes llega a 0
+    Gestante { padre_id: u32, dias_restantes: u32 },
+    // Recuperandose del ultimo parto, todavia no puede quedar preñada
+    Posparto { dias_restantes: u32 },
 }
 
 /// Animal (presa)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Animal {
     pub id: u32,
     pub especie: Especie,
     pub edad_dias: u32,
     pub peso_kg: f32,
     pub sexo: Sexo,
-    pub growth_fn: GrowthFunction,
+    pub crecimiento: Gompertz,
     pub edad_sacrificio: u32,  // Edad mínima para ser cazado por depredador
+    pub municipio: Municipio,  // Parche del valle donde vive
+    pub posicion: Posicion,    // Lugar dentro del municipio
+    pub alimentacion: f32,     // Fraccion del forraje que necesitaba y alcanzo a comer hoy
+    pub dias_desnutrido: u32,  // Dias seguidos comiendo menos de la mitad de lo que necesita
+    pub reproduccion: EstadoReproductivo, // Solo cambia en las hembras
+    pub salud: EstadoSalud,
 }
 
 impl Animal {
-    pub fn new(id: u32, especie: Especie, edad_inicial: u32, sexo: Sexo) -> Self {
-        let growth_fn = create_growth_function(especie.clone());
-        let peso_kg = (growth_fn)(edad_inicial);
-        let edad_sacrificio = especie.edad_sacrificio(); // Edad mínima para ser cazado
+    // Los parametros de la especie salen del archivo de especies del mundo
+    pub fn new(
+        id: u32,
+        especie: Especie,
+        edad_inicial: u32,
+        sexo: Sexo,
+        municipio: Municipio,
+        posicion: Posicion,
+        parametros: &ParametrosEspecie,
+    ) -> Self {
+        let crecimiento = parametros.crecimiento;
+        let peso_kg = crecimiento.peso(edad_inicial);
+        let edad_sacrificio = parametros.edad_sacrificio(); // Edad mínima para ser cazado
         Self {
             id,
             especie,
             edad_dias: edad_inicial,
             peso_kg,
             sexo,
-            growth_fn,
+            crecimiento,
             edad_sacrificio,
+            municipio,
+            posicion,
+            alimentacion: 1.0,
+            dias_desnutrido: 0,
+            reproduccion: EstadoReproductivo::Disponible,
+            salud: EstadoSalud::Susceptible,
         }
     }
 
-    // Funcion que permite que cada dia que pase se sume uno
-    // y el peso se basa de la edad con la grompertz
-    pub fn envejecer_un_dia(&mut self) {
+    pub fn esta_gestante(&self) -> bool {
+        matches!(self.reproduccion, EstadoReproductivo::Gestante { .. })
+    }
+
+    // Avanza un dia la preñez o la recuperacion del parto. Si hoy termina la gestacion
+    // devuelve el id del padre (hay parto) y la hembra pasa al posparto
+    pub fn avanzar_ciclo_reproductivo(&mut self, parametros: &ParametrosEspecie) -> Option<u32> {
+        match self.reproduccion {
+            EstadoReproductivo::Disponible => None,
+            EstadoReproductivo::Gestante { padre_id, dias_restantes } => {
+                if dias_restantes > 1 {
+                    self.reproduccion = EstadoReproductivo::Gestante { padre_id, dias_restantes: dias_restantes - 1 };
+                    return None;
+                }
+                self.reproduccion = if parametros.dias_posparto > 0 {
+                    EstadoReproductivo::Posparto { dias_restantes: parametros.dias_posparto }
+                } else {
+                    EstadoReproductivo::Disponible
+                };
+                Some(padre_id)
+            }
+            EstadoReproductivo::Posparto { dias_restantes } => {
+                self.reproduccion = if dias_restantes > 1 {
+                    EstadoReproductivo::Posparto { dias_restantes: dias_restantes - 1 }
+                } else {
+                    EstadoReproductivo::Disponible
+                };
+                None
+            }
+        }
+    }
+
+    // Forraje que necesita comer en el dia segun su peso
+    pub fn demanda_forraje(&self, parametros: &ParametrosEspecie) -> f32 {
+        self.peso_kg * parametros.consumo_forraje
+    }
+
+    // Funcion que permite que cada dia que pase se sume uno. El peso busca el de la
+    // curva de gompertz para su edad, pero solo lo alcanza si comio todo lo que necesitaba;
+    // si comio la mitad, crece la mitad de lo que le falta
+    pub fn envejecer_un_dia(&mut self, alimentacion: f32) {
         self.edad_dias += 1;
-        self.peso_kg = (self.growth_fn)(self.edad_dias);
+        self.alimentacion = alimentacion;
+        let peso_esperado = self.crecimiento.peso(self.edad_dias);
+        if peso_esperado > self.peso_kg {
+            self.peso_kg += (peso_esperado - self.peso_kg) * alimentacion;
+        }
+        if alimentacion < 0.5 {
+            self.dias_desnutrido += 1;
+        } else {
+            self.dias_desnutrido = 0;
+        }
+    }
+
+    // Si lleva demasiados dias sin comer lo suficiente se muere de hambre
+    pub fn murio_de_hambre(&self, parametros: &ParametrosEspecie) -> bool {
+        self.dias_desnutrido > parametros.dias_max_desnutrido
     }
 
     // Verificar si el animal puede ser cazado (ha alcanzado edad de sacrificio)
@@ -71,52 +167,95 @@ impl Animal {
         self.edad_dias >= self.edad_sacrificio
     }
 
-    // Verificar si el animal puede enfermarse (probabilidad diaria)
-    pub fn verificar_enfermedad(&self) -> bool {
-        let mut rng = rand::thread_rng();
-        rng.gen_bool(self.especie.probabilidad_enfermedad() as f64)
+    // Probabilidad de que un ataque contra esta presa funcione. Las crias livianas y las viejas
+    // son mas faciles, y cada vecino de la manada la protege un poco
+    pub fn probabilidad_ser_cazado(&self, parametros: &ParametrosEspecie, vecinos_manada: usize) -> f32 {
+        let defensa = &parametros.defensa;
+        let mut probabilidad = defensa.exito_base;
+        probabilidad *= (parametros.crecimiento.a / self.peso_kg.max(f32::EPSILON)).sqrt().clamp(0.5, 2.0);
+        if self.edad_dias as f32 >= parametros.edad_maxima as f32 * 0.8 {
+            probabilidad *= defensa.factor_vejez;
+        }
+        probabilidad /= 1.0 + defensa.efecto_manada * vecinos_manada as f32;
+        // clamp deja pasar NaN y gen_bool no lo acepta: un ataque imposible de calcular falla
+        if probabilidad.is_nan() {
+            return 0.0;
+        }
+        probabilidad.clamp(0.0, 1.0)
     }
-}
 
-// Lo clonamos manualmente para evitar problemas con el Box<dyn Fn(u32) porque no daba
-// problemas proque no nos dejaba clonarlo automaticamente
-impl Clone for Animal {
-    fn clone(&self) -> Self {
-        let growth_fn = create_growth_function(self.especie.clone());
-        Self {
-            id: self.id,
-            especie: self.especie.clone(),
-            edad_dias: self.edad_dias,
-            peso_kg: self.peso_kg,
-            sexo: self.sexo.clone(),
-            growth_fn,
-            edad_sacrificio: self.edad_sacrificio,
+    // Avanza un dia la infeccion. Si hoy se termina dice si murio o se recupero (y queda inmune).
+    // El rng lo pone el mundo para que la simulacion se pueda repetir con la misma semilla
+    pub fn avanzar_infeccion<R: Rng>(&mut self, parametros: &ParametrosEnfermedad, rng: &mut R) -> Option<FinInfeccion> {
+        let EstadoSalud::Infectado { dias_restantes } = self.salud else {
+            return None;
+        };
+        if dias_restantes > 1 {
+            self.salud = EstadoSalud::Infectado { dias_restantes: dias_restantes - 1 };
+            return None;
+        }
+        if rng.gen_bool(parametros.letalidad as f64) {
+            Some(FinInfeccion::Murio)
+        } else {
+            self.salud = EstadoSalud::Recuperado;
+            Some(FinInfeccion::Recupero)
         }
     }
 }
 
+/// Lo que queda de una presa cazada. El depredador que la cazo come de ella en los dias siguientes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cadaver {
+    pub depredador_id: u32,
+    pub presa_id: u32,
+    pub especie: Especie,
+    // Carne comestible que queda
+    pub carne_kg: f32,
+}
+
+/// Dias seguidos sin comer el nivel minimo con los que se muere un depredador
+pub const DIAS_MAX_SIN_COMER: u32 = 5;
+
 /// Depredador 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Depredador {
     pub id: u32,
+    pub sexo: Sexo,
     pub edad_dias: u32,
+    pub edad_adulta: u32,
     pub edad_maxima: u32,
     pub reserva_kg: f32,
     pub dias_sin_comer: u32,
     pub nivel_minimo_diario: f32,    // Nivel mínimo que debe consumir para no enfermar
     pub nivel_optimo_diario: f32,    // Nivel óptimo que busca consumir
+    pub municipio: Municipio,
+    pub posicion: Posicion,
+    pub estrategia: Estrategia,      // Como escoge que presa cazar
 }
 
 impl Depredador {
-    pub fn new(id: u32) -> Self {
+    // Los parametros salen de la seccion [depredador] del archivo de especies
+    pub fn new(
+        id: u32,
+        sexo: Sexo,
+        edad_inicial: u32,
+        municipio: Municipio,
+        posicion: Posicion,
+        parametros: &ParametrosDepredador,
+    ) -> Self {
         Self {
             id,
-            edad_dias: 0,
-            edad_maxima: 4000,
+            sexo,
+            edad_dias: edad_inicial,
+            edad_adulta: parametros.edad_adulta,
+            edad_maxima: parametros.edad_maxima,
             reserva_kg: 0.0,
             dias_sin_comer: 0,
-            nivel_minimo_diario: 1.0,   // 1 kg mínimo diario
-            nivel_optimo_diario: 3.0,   // 3 kg óptimo diario
+            nivel_minimo_diario: parametros.nivel_minimo_diario,
+            nivel_optimo_diario: parametros.nivel_optimo_diario,
+            municipio,
+            posicion,
+            estrategia: parametros.estrategia.clone(),
         }
     }
 
@@ -143,73 +282,156 @@ impl Depredador {
         self.reserva_kg < self.nivel_optimo_diario
     }
 
-    // Cuando el depredador caza se le suma el peso del animal a la reserva del depredador
-    pub fn cazar(&mut self, presa: &Animal) {
-        self.reserva_kg += presa.peso_kg;
+    // Un ataque que falla le cuesta parte de la reserva
+    pub fn gastar_ataque_fallido(&mut self, costo_kg: f32) {
+        self.reserva_kg = (self.reserva_kg - costo_kg).max(0.0);
+    }
+
+    // Come de un cadaver con disponible_kg de carne, hasta llenar el estomago o la reserva.
+    // Devuelve cuantos kg se comio
+    pub fn comer(&mut self, disponible_kg: f32, parametros: &ParametrosDepredador) -> f32 {
+        let espacio = (parametros.reserva_maxima_kg - self.reserva_kg).max(0.0);
+        let comido = disponible_kg.min(parametros.capacidad_estomago_kg).min(espacio);
+        self.reserva_kg += comido;
+        comido
+    }
+
+    // Ya puede reproducirse
+    pub fn es_adulto(&self) -> bool {
+        self.edad_dias >= self.edad_adulta
+    }
+
+    // Probabilidad de tener crias hoy: depende de cuanta reserva tenga.
+    // Con hambre (menos del nivel optimo) no se reproduce, y con una reserva de
+    // dias_reserva_reproduccion veces el optimo llega a la tasa completa
+    pub fn probabilidad_reproduccion(&self, parametros: &ParametrosDepredador) -> f32 {
+        if self.reserva_kg < self.nivel_optimo_diario {
+            return 0.0;
+        }
+        let reserva_completa = self.nivel_optimo_diario * parametros.dias_reserva_reproduccion;
+        parametros.tasa_reproduccion_diaria * (self.reserva_kg / reserva_completa).min(1.0)
+    }
+
+    // Que tan bien comido esta segun su reserva
+    pub fn estado_alimentacion(&self) -> EstadoAlimentacion {
+        if self.reserva_kg >= self.nivel_optimo_diario {
+            EstadoAlimentacion::Optimo
+        } else if self.reserva_kg >= self.nivel_minimo_diario {
+            EstadoAlimentacion::Minimo
+        } else {
+            EstadoAlimentacion::Hambriento
+        }
     }
 
     // Funcion que nos dice que si cumple todas las condiciones como dias sin comer menor a 5 y las demas seguira vivo
     pub fn esta_vivo(&self) -> bool {
-        self.dias_sin_comer < 5 && self.edad_dias < self.edad_maxima
+        self.dias_sin_comer < DIAS_MAX_SIN_COMER && self.edad_dias < self.edad_maxima
+    }
+}
+
+/// Estado de alimentacion del depredador segun su reserva
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EstadoAlimentacion {
+    Optimo,
+    Minimo,
+    Hambriento,
+}
+
+impl fmt::Display for EstadoAlimentacion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let texto = match self {
+            EstadoAlimentacion::Optimo => "Óptimo",
+            EstadoAlimentacion::Minimo => "Mínimo",
+            EstadoAlimentacion::Hambriento => "Hambriento",
+        };
+        write!(f, "{}", texto)
     }
 }
 
-/// Parametros de las especies
+/// Parametros de las especies (los valores vienen del archivo de especies, ver especies.rs)
 pub trait ComportamientoAnimal {
     fn edad_adulta(&self) -> u32;
     fn edad_maxima(&self) -> u32;
     fn edad_sacrificio(&self) -> u32;           // Nueva: edad mínima para ser cazado
     fn tasa_reproduccion_diaria(&self) -> f32;
-    fn probabilidad_enfermedad(&self) -> f32;   // Nueva: probabilidad diaria de enfermarse
+    fn probabilidad_enfermedad(&self) -> f32;   // Probabilidad diaria de traer la enfermedad de afuera
     fn max_crias_por_parto(&self) -> u8;
 }
 
-impl ComportamientoAnimal for Especie {
-    fn edad_adulta(&self) -> u32 {
-        match self {
-            Especie::Conejo => 90,
-            Especie::Cabra => 365,
-            Especie::Vaca => 500,
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::especies::ConfigEspecies;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
-    fn edad_maxima(&self) -> u32 {
-        match self {
-            Especie::Conejo => 2000,
-            Especie::Cabra => 5000,
-            Especie::Vaca => 6000,
+    // Dia del parto y dia en que vuelve a estar disponible, contando desde el dia en que quedo preñada
+    fn dias_del_ciclo(parametros: &ParametrosEspecie) -> (Option<u32>, Option<u32>) {
+        let mut hembra = Animal::new(1, Especie::Conejo, 200, Sexo::Hembra, Municipio::Medellin, Posicion::default(), parametros);
+        // Asi la deja Mundo::paso_dia el dia que queda preñada
+        hembra.reproduccion = EstadoReproductivo::Gestante { padre_id: 7, dias_restantes: parametros.dias_gestacion };
+        let (mut parto, mut disponible) = (None, None);
+        for dia in 1..=200 {
+            if let Some(padre_id) = hembra.avanzar_ciclo_reproductivo(parametros) {
+                assert_eq!(padre_id, 7);
+                parto = Some(dia);
+            }
+            if parto.is_some() && disponible.is_none() && hembra.reproduccion == EstadoReproductivo::Disponible {
+                disponible = Some(dia);
+            }
         }
+        (parto, disponible)
     }
 
-    fn edad_sacrificio(&self) -> u32 {
-        match self {
-            Especie::Conejo => 120,   // Pueden ser cazados después de los 120 días
-            Especie::Cabra => 450,    // Pueden ser cazados después de los 450 días  
-            Especie::Vaca => 600,     // Pueden ser cazados después de los 600 días
-        }
+    #[test]
+    fn la_gestacion_y_el_posparto_duran_lo_configurado() {
+        let mut parametros = ConfigEspecies::default().conejo;
+        let (gestacion, posparto) = (parametros.dias_gestacion, parametros.dias_posparto);
+        assert_eq!(dias_del_ciclo(&parametros), (Some(gestacion), Some(gestacion + posparto)));
+
+        // Sin posparto puede volver a quedar preñada el mismo dia del parto
+        parametros.dias_posparto = 0;
+        assert_eq!(dias_del_ciclo(&parametros), (Some(gestacion), Some(gestacion)));
     }
 
-    fn tasa_reproduccion_diaria(&self) -> f32 {
-        match self {
-            Especie::Conejo => 0.2,
-            Especie::Cabra => 0.05,
-            Especie::Vaca => 0.02,
+    // Dias que pasan desde que se contagia hasta que la infeccion termina y como termina
+    fn duracion_infeccion(parametros: &ParametrosEnfermedad) -> (u32, FinInfeccion, EstadoSalud) {
+        let conejo = ConfigEspecies::default().conejo;
+        let mut presa = Animal::new(1, Especie::Conejo, 200, Sexo::Hembra, Municipio::Medellin, Posicion::default(), &conejo);
+        presa.salud = EstadoSalud::Infectado { dias_restantes: parametros.dias_infeccion };
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for dia in 1..=100 {
+            if let Some(fin) = presa.avanzar_infeccion(parametros, &mut rng) {
+                return (dia, fin, presa.salud);
+            }
         }
+        panic!("la infeccion no termino en 100 dias");
     }
 
-    fn probabilidad_enfermedad(&self) -> f32 {
-        match self {
-            Especie::Conejo => 0.01,  
-            Especie::Cabra => 0.08, 
-            Especie::Vaca => 0.05,   
-        }
+    #[test]
+    fn la_infeccion_dura_dias_infeccion() {
+        let mut parametros = ConfigEspecies::default().conejo.enfermedad;
+        parametros.letalidad = 0.0;
+        assert_eq!(
+            duracion_infeccion(&parametros),
+            (parametros.dias_infeccion, FinInfeccion::Recupero, EstadoSalud::Recuperado)
+        );
+
+        parametros.letalidad = 1.0;
+        parametros.dias_infeccion = 1;
+        assert_eq!(duracion_infeccion(&parametros).0, 1);
+        assert_eq!(duracion_infeccion(&parametros).1, FinInfeccion::Murio);
     }
 
-    fn max_crias_por_parto(&self) -> u8 {
-        match self {
-            Especie::Conejo => 5,
-            Especie::Cabra => 2,
-            Especie::Vaca => 1,
+    #[test]
+    fn las_recuperadas_y_las_sanas_no_avanzan_infeccion() {
+        let conejo = ConfigEspecies::default().conejo;
+        let mut presa = Animal::new(1, Especie::Conejo, 200, Sexo::Hembra, Municipio::Medellin, Posicion::default(), &conejo);
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for salud in [EstadoSalud::Susceptible, EstadoSalud::Recuperado] {
+            presa.salud = salud;
+            assert_eq!(presa.avanzar_infeccion(&conejo.enfermedad, &mut rng), None);
+            assert_eq!(presa.salud, salud);
         }
     }
 }
@@ -0,0 +1,144 @@
+use crate::especies::ErrorConfig;
+use crate::world::Mundo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Version del formato de los archivos guardados, se sube cada vez que cambia el estado del mundo
+pub const VERSION_INSTANTANEA: u32 = 15;
+
+// Lo que se escribe en el archivo
+#[derive(Serialize)]
+struct Instantanea<'a> {
+    version: u32,
+    mundo: &'a Mundo,
+}
+
+// Se lee primero solo la version, para no intentar leer un mundo de otro formato
+#[derive(Deserialize)]
+struct InstantaneaLeida {
+    version: u32,
+    mundo: serde_json::Value,
+}
+
+/// Errores al guardar o cargar un mundo
+#[derive(Debug)]
+pub enum ErrorInstantanea {
+    Io(io::Error),
+    Formato(serde_json::Error),
+    Version { encontrada: u32, esperada: u32 },
+    // Los parametros de las especies guardados no pasan la validacion (el archivo se cambio a mano)
+    Especies(ErrorConfig),
+}
+
+impl fmt::Display for ErrorInstantanea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorInstantanea::Io(e) => write!(f, "no se pudo leer o escribir el archivo: {}", e),
+            ErrorInstantanea::Formato(e) => write!(f, "el archivo no es un mundo valido: {}", e),
+            ErrorInstantanea::Version { encontrada, esperada } => write!(
+                f,
+                "el archivo es de la version {} y este programa usa la version {}",
+                encontrada, esperada
+            ),
+            ErrorInstantanea::Especies(e) => write!(f, "el mundo guardado tiene especies invalidas: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ErrorInstantanea {}
+
+impl From<io::Error> for ErrorInstantanea {
+    fn from(e: io::Error) -> Self {
+        ErrorInstantanea::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ErrorInstantanea {
+    fn from(e: serde_json::Error) -> Self {
+        ErrorInstantanea::Formato(e)
+    }
+}
+
+impl Mundo {
+    // Guarda el estado completo del mundo (incluido el generador aleatorio) en un archivo
+    pub fn guardar(&self, ruta: impl AsRef<Path>) -> Result<(), ErrorInstantanea> {
+        let mut archivo = BufWriter::new(File::create(ruta)?);
+        let instantanea = Instantanea { version: VERSION_INSTANTANEA, mundo: self };
+        serde_json::to_writer(&mut archivo, &instantanea)?;
+        archivo.flush()?;
+        Ok(())
+    }
+
+    // Carga un mundo guardado, sigue exactamente donde quedo (sin observadores suscritos)
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Mundo, ErrorInstantanea> {
+        let archivo = BufReader::new(File::open(ruta)?);
+        let leida: InstantaneaLeida = serde_json::from_reader(archivo)?;
+        if leida.version != VERSION_INSTANTANEA {
+            return Err(ErrorInstantanea::Version {
+                encontrada: leida.version,
+                esperada: VERSION_INSTANTANEA,
+            });
+        }
+        let mundo: Mundo = serde_json::from_value(leida.mundo)?;
+        // Se revisan igual que al leer un archivo de especies, para no fallar mas adelante en la simulacion
+        mundo.especies.validar().map_err(ErrorInstantanea::Especies)?;
+        Ok(mundo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tests::mundo_de_prueba;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // Archivo temporal distinto para cada prueba, que corren al tiempo
+    fn archivo_temporal(nombre: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("predator_prey_{}_{}.json", std::process::id(), nombre))
+    }
+
+    #[test]
+    fn guardar_y_cargar_sigue_igual_que_sin_interrumpir() {
+        let mut seguido = mundo_de_prueba(42);
+        let esperados: Vec<String> = (0..60).map(|_| seguido.paso_dia().to_string()).collect();
+
+        let mut mundo = mundo_de_prueba(42);
+        let mut obtenidos: Vec<String> = (0..30).map(|_| mundo.paso_dia().to_string()).collect();
+        let ruta = archivo_temporal("seguir");
+        mundo.guardar(&ruta).unwrap();
+        let cargado = Mundo::cargar(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        let mut mundo = cargado.unwrap();
+        obtenidos.extend((0..30).map(|_| mundo.paso_dia().to_string()));
+
+        assert_eq!(obtenidos, esperados);
+    }
+
+    #[test]
+    fn rechaza_especies_invalidas() {
+        let ruta = archivo_temporal("especies");
+        mundo_de_prueba(42).guardar(&ruta).unwrap();
+        let texto = fs::read_to_string(&ruta).unwrap();
+        assert!(texto.contains(r#""max_crias_por_parto":5"#));
+        fs::write(&ruta, texto.replacen(r#""max_crias_por_parto":5"#, r#""max_crias_por_parto":0"#, 1)).unwrap();
+        let cargado = Mundo::cargar(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        assert!(matches!(cargado, Err(ErrorInstantanea::Especies(ErrorConfig::Invalido(_)))));
+    }
+
+    #[test]
+    fn rechaza_otra_version() {
+        let ruta = archivo_temporal("version");
+        fs::write(&ruta, r#"{"version": 0, "mundo": {}}"#).unwrap();
+        let cargado = Mundo::cargar(&ruta);
+        fs::remove_file(&ruta).unwrap();
+        assert!(matches!(
+            cargado,
+            Err(ErrorInstantanea::Version { encontrada: 0, esperada: VERSION_INSTANTANEA })
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Los diez municipios del Valle de Aburrá, de norte a sur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Municipio {
+    Barbosa,
+    Girardota,
+    Copacabana,
+    Bello,
+    Medellin,
+    Itagui,
+    Envigado,
+    Sabaneta,
+    LaEstrella,
+    Caldas,
+}
+
+impl Municipio {
+    pub const TODOS: [Municipio; 10] = [
+        Municipio::Barbosa,
+        Municipio::Girardota,
+        Municipio::Copacabana,
+        Municipio::Bello,
+        Municipio::Medellin,
+        Municipio::Itagui,
+        Municipio::Envigado,
+        Municipio::Sabaneta,
+        Municipio::LaEstrella,
+        Municipio::Caldas,
+    ];
+
+    // Posicion en TODOS
+    pub fn indice(&self) -> usize {
+        *self as usize
+    }
+
+    // Municipios con los que tiene frontera, los animales solo migran entre vecinos
+    pub fn vecinos(&self) -> &'static [Municipio] {
+        use Municipio::*;
+        match self {
+            Barbosa => &[Girardota],
+            Girardota => &[Barbosa, Copacabana],
+            Copacabana => &[Girardota, Bello, Medellin],
+            Bello => &[Copacabana, Medellin],
+            Medellin => &[Copacabana, Bello, Itagui, Envigado, LaEstrella],
+            Itagui => &[Medellin, Envigado, Sabaneta, LaEstrella],
+            Envigado => &[Medellin, Itagui, Sabaneta],
+            Sabaneta => &[Itagui, Envigado, LaEstrella, Caldas],
+            LaEstrella => &[Medellin, Itagui, Sabaneta, Caldas],
+            Caldas => &[Sabaneta, LaEstrella],
+        }
+    }
+}
+
+impl fmt::Display for Municipio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nombre = match self {
+            Municipio::Barbosa => "Barbosa",
+            Municipio::Girardota => "Girardota",
+            Municipio::Copacabana => "Copacabana",
+            Municipio::Bello => "Bello",
+            Municipio::Medellin => "Medellín",
+            Municipio::Itagui => "Itagüí",
+            Municipio::Envigado => "Envigado",
+            Municipio::Sabaneta => "Sabaneta",
+            Municipio::LaEstrella => "La Estrella",
+            Municipio::Caldas => "Caldas",
+        };
+        write!(f, "{}", nombre)
+    }
+}
+
+/// Un municipio visto como parche de habitat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parche {
+    pub municipio: Municipio,
+    // Kg de presas que el parche puede sostener
+    pub capacidad_carga_kg: f32,
+    // Que tan bueno es el habitat, de 0 (ciudad) a 1 (campo abierto)
+    pub calidad_habitat: f32,
+    // Pasto y forraje que hay ahora y el maximo que puede haber (kg)
+    pub forraje_kg: f32,
+    pub capacidad_forraje_kg: f32,
+    // Crecimiento diario del forraje cuando hay poco (rebrote logistico)
+    pub tasa_rebrote: f32,
+}
+
+impl Parche {
+    // Que tan lleno esta el parche (1.0 = en su capacidad de carga)
+    pub fn ocupacion(&self, biomasa_kg: f32) -> f32 {
+        biomasa_kg / self.capacidad_carga_kg
+    }
+
+    // Factor que frena la reproduccion cuando el parche se llena (crecimiento logistico)
+    // y que es menor en habitats de mala calidad
+    pub fn factor_reproduccion(&self, biomasa_kg: f32) -> f32 {
+        self.calidad_habitat * (1.0 - self.ocupacion(biomasa_kg)).max(0.0)
+    }
+
+    // Que tan atractivo es para un animal que esta migrando
+    pub fn atractivo(&self, biomasa_kg: f32) -> f32 {
+        self.calidad_habitat * (1.0 - self.ocupacion(biomasa_kg)).max(0.05)
+    }
+
+    // Las presas se comen demanda_kg de forraje. Devuelve que fraccion de lo que
+    // necesitaban alcanzaron a comer (1.0 = todo)
+    pub fn pastorear(&mut self, demanda_kg: f32) -> f32 {
+        if demanda_kg <= 0.0 {
+            return 1.0;
+        }
+        let comido = demanda_kg.min(self.forraje_kg);
+        self.forraje_kg -= comido;
+        comido / demanda_kg
+    }
+
+    // El forraje vuelve a crecer (logistico), mas rapido en lluvias segun factor_estacion.
+    // Nunca se acaba del todo porque siempre queda algo de raiz y semilla para rebrotar
+    pub fn rebrotar(&mut self, factor_estacion: f32) {
+        let minimo = self.capacidad_forraje_kg * 0.01;
+        let f = self.forraje_kg.max(minimo);
+        let tasa = self.tasa_rebrote * factor_estacion;
+        self.forraje_kg = (f + tasa * f * (1.0 - f / self.capacidad_forraje_kg)).min(self.capacidad_forraje_kg);
+    }
+}
+
+/// El Valle de Aburrá dividido en parches, uno por municipio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Region {
+    pub parches: Vec<Parche>,
+}
+
+impl Default for Region {
+    // Capacidad y calidad aproximadas segun cuanto terreno rural le queda a cada municipio.
+    // El forraje maximo es el doble de la capacidad de carga y el rebrote es mejor en buen habitat
+    fn default() -> Self {
+        let parches = Municipio::TODOS
+            .iter()
+            .map(|&municipio| {
+                let (capacidad_carga_kg, calidad_habitat) = match municipio {
+                    Municipio::Barbosa => (20000.0, 0.9),
+                    Municipio::Girardota => (12000.0, 0.8),
+                    Municipio::Copacabana => (10000.0, 0.7),
+                    Municipio::Bello => (12000.0, 0.6),
+                    Municipio::Medellin => (25000.0, 0.5),
+                    Municipio::Itagui => (3000.0, 0.3),
+                    Municipio::Envigado => (10000.0, 0.6),
+                    Municipio::Sabaneta => (2000.0, 0.3),
+                    Municipio::LaEstrella => (5000.0, 0.6),
+                    Municipio::Caldas => (15000.0, 0.8),
+                };
+                let capacidad_forraje_kg = capacidad_carga_kg * 2.0;
+                Parche {
+                    municipio,
+                    capacidad_carga_kg,
+                    calidad_habitat,
+                    forraje_kg: capacidad_forraje_kg,
+                    capacidad_forraje_kg,
+                    tasa_rebrote: 0.1 * calidad_habitat,
+                }
+            })
+            .collect();
+        Self { parches }
+    }
+}
+
+impl Region {
+    pub fn parche(&self, municipio: Municipio) -> &Parche {
+        &self.parches[municipio.indice()]
+    }
+
+    // Municipio al azar, los de mas capacidad de carga salen mas seguido
+    pub fn municipio_al_azar<R: Rng>(&self, rng: &mut R) -> Municipio {
+        let pesos: Vec<f32> = self.parches.iter().map(|p| p.capacidad_carga_kg).collect();
+        Municipio::TODOS[elegir_por_peso(&pesos, rng)]
+    }
+
+    // Vecino al que se va un animal que migra, los de mas peso salen mas seguido
+    pub fn vecino_al_azar<R: Rng>(&self, desde: Municipio, peso: impl Fn(&Parche) -> f32, rng: &mut R) -> Municipio {
+        let vecinos = desde.vecinos();
+        let pesos: Vec<f32> = vecinos.iter().map(|v| peso(self.parche(*v))).collect();
+        vecinos[elegir_por_peso(&pesos, rng)]
+    }
+}
+
+// Indice al azar con probabilidad proporcional a su peso (si todos son 0, cualquiera)
+fn elegir_por_peso<R: Rng>(pesos: &[f32], rng: &mut R) -> usize {
+    match WeightedIndex::new(pesos) {
+        Ok(distribucion) => distribucion.sample(rng),
+        Err(_) => rng.gen_range(0..pesos.len()),
+    }
+}
@@ -0,0 +1,314 @@
+use crate::enfermedad::ConteoSir;
+use crate::estaciones::Estacion;
+use crate::estrategia::Estrategia;
+use crate::models::{EstadoAlimentacion, Especie, Sexo};
+use crate::region::Municipio;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Una caza exitosa de un depredador
+#[derive(Debug, Clone)]
+pub struct Caza {
+    pub depredador_id: u32,
+    pub presa_id: u32,
+    pub especie: Especie,
+    pub peso_kg: f32,
+    pub edad_dias: u32,
+    // Parte del peso que se puede comer
+    pub carne_kg: f32,
+}
+
+/// Un ataque de un depredador que la presa logro evitar
+#[derive(Debug, Clone)]
+pub struct CazaFallida {
+    pub depredador_id: u32,
+    pub presa_id: u32,
+    pub especie: Especie,
+    pub peso_kg: f32,
+    // Probabilidad que tenia el ataque de funcionar
+    pub probabilidad: f32,
+}
+
+/// Lo que le paso a cada depredador al final del dia (en el orden en que se revisaron)
+#[derive(Debug, Clone)]
+pub enum DiaDepredador {
+    // Consumio el nivel minimo de su reserva
+    Comio { depredador_id: u32, consumido_kg: f32, reserva_kg: f32 },
+    // No le alcanzo la reserva para el minimo diario
+    NoComio { depredador_id: u32, dias_sin_comer: u32, reserva_kg: f32 },
+    // Murio de hambre o de viejo
+    Murio { depredador_id: u32, edad_dias: u32, dias_sin_comer: u32 },
+}
+
+/// Una presa que murio (por enfermedad, vejez o hambre)
+#[derive(Debug, Clone)]
+pub struct MuertePresa {
+    pub presa_id: u32,
+    pub especie: Especie,
+    pub edad_dias: u32,
+}
+
+/// Una presa que se contagio en el dia
+#[derive(Debug, Clone)]
+pub struct Contagio {
+    pub presa_id: u32,
+    pub especie: Especie,
+    // Cuantos vecinos infectados tenia (0 = la trajo de afuera)
+    pub vecinos_infectados: usize,
+}
+
+/// Una presa que sobrevivio a la enfermedad y quedo inmune
+#[derive(Debug, Clone)]
+pub struct Recuperacion {
+    pub presa_id: u32,
+    pub especie: Especie,
+}
+
+/// Una hembra que quedo preñada en el dia
+#[derive(Debug, Clone)]
+pub struct Concepcion {
+    pub madre_id: u32,
+    pub padre_id: u32,
+    pub especie: Especie,
+    // Dias que faltan para el parto
+    pub dias_gestacion: u32,
+}
+
+/// Una cria que nacio en el dia
+#[derive(Debug, Clone)]
+pub struct Nacimiento {
+    pub cria_id: u32,
+    pub madre_id: u32,
+    pub padre_id: u32,
+    pub especie: Especie,
+    pub sexo: Sexo,
+}
+
+/// Una cria de depredador que nacio en el dia
+#[derive(Debug, Clone)]
+pub struct NacimientoDepredador {
+    pub cria_id: u32,
+    pub madre_id: u32,
+    pub sexo: Sexo,
+}
+
+/// Un animal que se fue a un municipio vecino
+#[derive(Debug, Clone)]
+pub struct Migracion {
+    pub id: u32,
+    pub es_depredador: bool,
+    pub desde: Municipio,
+    pub hacia: Municipio,
+}
+
+/// Cuantos animales quedaron en un municipio al terminar el dia
+#[derive(Debug, Clone, Default)]
+pub struct ConteoMunicipio {
+    pub presas: BTreeMap<Especie, usize>,
+    pub depredadores: usize,
+    pub biomasa_kg: f32,
+    // Forraje que quedo en el municipio
+    pub forraje_kg: f32,
+    // Biomasa de presas sobre la capacidad de carga del municipio (1.0 = lleno)
+    pub ocupacion: f32,
+}
+
+impl ConteoMunicipio {
+    pub fn total_presas(&self) -> usize {
+        self.presas.values().sum()
+    }
+}
+
+/// Como quedo cada depredador vivo al terminar el dia
+#[derive(Debug, Clone)]
+pub struct ResumenDepredador {
+    pub id: u32,
+    pub sexo: Sexo,
+    pub edad_dias: u32,
+    pub reserva_kg: f32,
+    pub estado: EstadoAlimentacion,
+    pub dias_sin_comer: u32,
+    pub estrategia: Estrategia,
+}
+
+/// Todo lo que paso en un dia de la simulacion
+#[derive(Debug, Clone, Default)]
+pub struct ReporteDia {
+    pub dia: u32,
+    pub estacion: Estacion,
+    pub cazas: Vec<Caza>,
+    pub cazas_fallidas: Vec<CazaFallida>,
+    pub depredadores_dia: Vec<DiaDepredador>,
+    pub contagios: Vec<Contagio>,
+    pub recuperaciones: Vec<Recuperacion>,
+    pub muertes_enfermedad: Vec<MuertePresa>,
+    pub muertes_vejez: Vec<MuertePresa>,
+    pub muertes_hambre: Vec<MuertePresa>,
+    pub concepciones: Vec<Concepcion>,
+    pub nacimientos: Vec<Nacimiento>,
+    pub nacimientos_depredadores: Vec<NacimientoDepredador>,
+    pub migraciones: Vec<Migracion>,
+    // Carne de los cadaveres que se pudrio o que ya nadie va a comer
+    pub carne_desperdiciada_kg: f32,
+    // Carne que queda en los cadaveres al final del dia
+    pub carne_cadaveres_kg: f32,
+    // Cuantas presas quedaron de cada especie (solo aparecen las que tienen alguna)
+    pub totales: BTreeMap<Especie, usize>,
+    // Hembras preñadas de cada especie al final del dia
+    pub gestantes: BTreeMap<Especie, usize>,
+    // Susceptibles, infectadas y recuperadas de cada especie al final del dia
+    pub salud: BTreeMap<Especie, ConteoSir>,
+    pub depredadores: Vec<ResumenDepredador>,
+    // Conteo por municipio (solo aparecen los que tienen algun animal)
+    pub municipios: BTreeMap<Municipio, ConteoMunicipio>,
+}
+
+impl ReporteDia {
+    pub fn new(dia: u32) -> Self {
+        Self {
+            dia,
+            ..Default::default()
+        }
+    }
+
+    // Total de presas vivas al final del dia
+    pub fn total_presas(&self) -> usize {
+        self.totales.values().sum()
+    }
+}
+
+// El texto que muestra la interfaz en la caja del reporte
+impl fmt::Display for ReporteDia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "=== Día {} ===", self.dia)?;
+
+        for caza in &self.cazas {
+            writeln!(
+                f,
+                "Depredador #{} cazó presa ID {} ({:?}, {:.2} kg, {} días, {:.2} kg de carne).",
+                caza.depredador_id, caza.presa_id, caza.especie, caza.peso_kg, caza.edad_dias, caza.carne_kg
+            )?;
+        }
+
+        for fallida in &self.cazas_fallidas {
+            writeln!(
+                f,
+                "Depredador #{} falló al cazar presa ID {} ({:?}, {:.2} kg, {:.0}% de éxito).",
+                fallida.depredador_id,
+                fallida.presa_id,
+                fallida.especie,
+                fallida.peso_kg,
+                fallida.probabilidad * 100.0
+            )?;
+        }
+
+        for dia_dep in &self.depredadores_dia {
+            match dia_dep {
+                DiaDepredador::Comio { depredador_id, consumido_kg, reserva_kg } => writeln!(
+                    f,
+                    "Depredador #{} consumió {:.1} kg. Reserva: {:.2} kg",
+                    depredador_id, consumido_kg, reserva_kg
+                )?,
+                DiaDepredador::NoComio { depredador_id, dias_sin_comer, reserva_kg } => writeln!(
+                    f,
+                    "Depredador #{} no comió (lleva {} días sin comer). Reserva: {:.2} kg",
+                    depredador_id, dias_sin_comer, reserva_kg
+                )?,
+                DiaDepredador::Murio { depredador_id, edad_dias, dias_sin_comer } => writeln!(
+                    f,
+                    "Depredador #{} murió (Edad: {} días, Días sin comer: {}).",
+                    depredador_id, edad_dias, dias_sin_comer
+                )?,
+            }
+        }
+
+        if !self.contagios.is_empty() {
+            writeln!(f, "Se contagiaron {} presas.", self.contagios.len())?;
+        }
+        if !self.recuperaciones.is_empty() {
+            writeln!(f, "Se recuperaron {} presas.", self.recuperaciones.len())?;
+        }
+        for muerte in &self.muertes_enfermedad {
+            writeln!(f, "Presa ID {} ({:?}) murió por enfermedad.", muerte.presa_id, muerte.especie)?;
+        }
+
+        if !self.muertes_hambre.is_empty() {
+            writeln!(f, "Murieron {} presas de hambre.", self.muertes_hambre.len())?;
+        }
+
+        if !self.concepciones.is_empty() {
+            writeln!(f, "Quedaron preñadas {} hembras.", self.concepciones.len())?;
+        }
+        if !self.nacimientos.is_empty() {
+            let partos: BTreeSet<u32> = self.nacimientos.iter().map(|n| n.madre_id).collect();
+            writeln!(f, "Nacieron {} crías en {} partos.", self.nacimientos.len(), partos.len())?;
+        }
+        for nacimiento in &self.nacimientos_depredadores {
+            writeln!(
+                f,
+                "Nació el depredador #{} ({:?}), cría de #{}.",
+                nacimiento.cria_id, nacimiento.sexo, nacimiento.madre_id
+            )?;
+        }
+
+        if !self.migraciones.is_empty() {
+            let depredadores = self.migraciones.iter().filter(|m| m.es_depredador).count();
+            writeln!(
+                f,
+                "Migraron {} presas y {} depredadores entre municipios.",
+                self.migraciones.len() - depredadores,
+                depredadores
+            )?;
+        }
+
+        if self.carne_desperdiciada_kg > 0.0 {
+            writeln!(f, "Se desperdiciaron {:.2} kg de carne.", self.carne_desperdiciada_kg)?;
+        }
+
+        writeln!(f, "=== Resumen Día {} ===", self.dia)?;
+        writeln!(f, "Temporada: {}", self.estacion)?;
+        writeln!(f, "Total presas: {}", self.total_presas())?;
+        for (especie, count) in &self.totales {
+            match self.gestantes.get(especie) {
+                Some(gestantes) => writeln!(f, "- {:?}: {} ({} preñadas)", especie, count, gestantes)?,
+                None => writeln!(f, "- {:?}: {}", especie, count)?,
+            }
+        }
+
+        for (especie, sir) in &self.salud {
+            writeln!(
+                f,
+                "  {:?} S/I/R: {}/{}/{}",
+                especie, sir.susceptibles, sir.infectados, sir.recuperados
+            )?;
+        }
+
+        writeln!(f, "Depredadores vivos: {}", self.depredadores.len())?;
+        if self.carne_cadaveres_kg > 0.0 {
+            writeln!(f, "Carne en cadáveres: {:.2} kg", self.carne_cadaveres_kg)?;
+        }
+        for dep in &self.depredadores {
+            writeln!(
+                f,
+                "- Depredador #{} ({:?}) | Edad: {} días | Reserva: {:.2} kg | Estado: {} | Días sin comer: {} | Caza: {}",
+                dep.id, dep.sexo, dep.edad_dias, dep.reserva_kg, dep.estado, dep.dias_sin_comer, dep.estrategia
+            )?;
+        }
+
+        if !self.municipios.is_empty() {
+            writeln!(f, "=== Municipios ===")?;
+        }
+        for (municipio, conteo) in &self.municipios {
+            writeln!(
+                f,
+                "- {}: {} presas ({:.0}% de la capacidad) | Depredadores: {} | Forraje: {:.0} kg",
+                municipio,
+                conteo.total_presas(),
+                conteo.ocupacion * 100.0,
+                conteo.depredadores,
+                conteo.forraje_kg
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,110 +1,390 @@
 use rand::seq::SliceRandom;
-use rand::prelude::IteratorRandom;
-use rand::Rng;
-use crate::models::{Animal, ComportamientoAnimal, Depredador, Especie, Sexo};
-use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::apareamiento::elegir_padre;
+use crate::enfermedad::{EstadoSalud, FinInfeccion};
+use crate::especies::ConfigEspecies;
+use crate::estaciones::Estacion;
+use crate::estrategia::{Estrategia, EstrategiaCaza, EstrategiaNoRegistrada, RegistroEstrategias};
+use crate::eventos::{Evento, IdSuscripcion, Observador, Observadores};
+use crate::historial::Historial;
+use crate::mapa::{Mapa, Posicion};
+use crate::models::{Animal, Cadaver, ComportamientoAnimal, Depredador, Especie, EstadoReproductivo, Sexo};
+use crate::region::{Municipio, Region};
+use crate::reporte::{
+    Caza, CazaFallida, Concepcion, Contagio, DiaDepredador, Migracion, MuertePresa, Nacimiento, NacimientoDepredador,
+    Recuperacion, ReporteDia, ResumenDepredador,
+};
+
+/// Reglas opcionales del escenario que se quiere simular
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReglasEscenario {
+    // Maximo de depredadores vivos al mismo tiempo (None = sin limite)
+    pub max_depredadores: Option<u32>,
+}
 
 // Etructura del mundo
+#[derive(Serialize, Deserialize)]
 pub struct Mundo {
     pub dia_actual: u32,
     pub depredadores: Vec<Depredador>,
     pub presas: Vec<Animal>,
+    // Presas cazadas que todavia tienen carne
+    pub cadaveres: Vec<Cadaver>,
     pub contador_animales: u32,
+    // Para darle un id distinto a cada depredador
+    pub contador_depredadores: u32,
+    pub reglas: ReglasEscenario,
+    // Municipios del valle donde viven los animales
+    pub region: Region,
+    // Terreno de cada municipio, donde se mueven los animales
+    pub mapa: Mapa,
+    // Semilla con la que se creo el mundo, sirve para repetir una simulacion
+    pub semilla: u64,
+    // Parametros biologicos de las especies (se guardan con el mundo)
+    pub especies: ConfigEspecies,
+    // Conteos de cada dia desde el comienzo, para graficar y exportar a CSV
+    pub historial: Historial,
+    // Todo lo aleatorio de la simulacion sale de aqui (sexo, cazas, enfermedad, nacimientos)
+    rng: ChaCha8Rng,
+    // Quienes reciben los eventos de cada dia (no se guardan con el mundo)
+    #[serde(skip)]
+    observadores: Observadores,
+    // Estrategias de caza propias (tampoco se guardan)
+    #[serde(skip)]
+    estrategias: RegistroEstrategias,
+}
+
+impl Default for Mundo {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Definimos el mundo que empiece totalmente vacio
 impl Mundo {
+    // Mundo con una semilla al azar, cada ejecucion es distinta
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    // Mundo con una semilla fija, con la misma semilla y los mismos datos
+    // los reportes de cada dia salen identicos
+    pub fn with_seed(semilla: u64) -> Self {
+        Self::with_config(semilla, ConfigEspecies::default())
+    }
+
+    // Mundo con una semilla fija y las especies de un archivo de configuracion
+    pub fn with_config(semilla: u64, especies: ConfigEspecies) -> Self {
         Self {
             dia_actual: 0,
             depredadores: Vec::new(),
             presas: Vec::new(),
+            cadaveres: Vec::new(),
             contador_animales: 0,
+            contador_depredadores: 0,
+            reglas: ReglasEscenario::default(),
+            region: Region::default(),
+            mapa: Mapa::default(),
+            semilla,
+            especies,
+            historial: Historial::default(),
+            rng: ChaCha8Rng::seed_from_u64(semilla),
+            observadores: Observadores::default(),
+            estrategias: RegistroEstrategias::default(),
         }
     }
 
-    // Funcion para agregar depredadores, limitado a UNO solo según requisitos
+    // Registra un observador que recibira cada evento de la simulacion mientras pasa
+    pub fn suscribir(&mut self, observador: impl Observador + 'static) -> IdSuscripcion {
+        self.observadores.agregar(Box::new(observador))
+    }
+
+    // Quita un observador, devuelve false si ya no estaba
+    pub fn desuscribir(&mut self, id: IdSuscripcion) -> bool {
+        self.observadores.quitar(id)
+    }
+
+    // Funcion para agregar depredadores, cada uno con su propio id y en un municipio al azar.
+    // Empiezan adultos para que puedan reproducirse, igual que las presas empiezan cazables
     pub fn agregar_depredadores(&mut self, cantidad: u32) {
-        // Solo permitir UN depredador según los requisitos
-        let cantidad_real = if cantidad > 0 { 1 } else { 0 };
-        for i in 0..cantidad_real {
-            self.depredadores.push(Depredador::new(i));
+        for _ in 0..self.cupo_depredadores(cantidad) {
+            let municipio = self.region.municipio_al_azar(&mut self.rng);
+            self.agregar_depredador(municipio);
+        }
+    }
+
+    // Agrega una estrategia de caza propia con ese nombre, para usarla con Estrategia::Propia.
+    // Si ya habia una con el mismo nombre la reemplaza
+    pub fn registrar_estrategia(&mut self, nombre: &str, estrategia: impl EstrategiaCaza + Send + 'static) {
+        self.estrategias.registrar(nombre.to_string(), Box::new(estrategia));
+    }
+
+    // Igual que agregar_depredadores pero con otra estrategia de caza que la del archivo de especies.
+    // Una estrategia propia tiene que estar registrada antes, si no no se agrega ninguno
+    pub fn agregar_depredadores_con(
+        &mut self,
+        estrategia: Estrategia,
+        cantidad: u32,
+    ) -> Result<(), EstrategiaNoRegistrada> {
+        if let Estrategia::Propia(nombre) = &estrategia
+            && !self.estrategias.contiene(nombre)
+        {
+            return Err(EstrategiaNoRegistrada(nombre.clone()));
+        }
+        for _ in 0..self.cupo_depredadores(cantidad) {
+            let municipio = self.region.municipio_al_azar(&mut self.rng);
+            self.agregar_depredador(municipio);
+            if let Some(dep) = self.depredadores.last_mut() {
+                dep.estrategia = estrategia.clone();
+            }
+        }
+        Ok(())
+    }
+
+    // Estrategias propias que usan los depredadores y no estan registradas (sin repetir).
+    // Despues de cargar un mundo sirve para saber cuales hay que registrar: mientras tanto
+    // esos depredadores no cazan
+    pub fn estrategias_sin_registrar(&self) -> Vec<String> {
+        let mut nombres: Vec<String> = self
+            .depredadores
+            .iter()
+            .filter_map(|dep| match &dep.estrategia {
+                Estrategia::Propia(nombre) if !self.estrategias.contiene(nombre) => Some(nombre.clone()),
+                _ => None,
+            })
+            .collect();
+        nombres.sort();
+        nombres.dedup();
+        nombres
+    }
+
+    fn agregar_depredador(&mut self, municipio: Municipio) {
+        let sexo = if self.rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
+        let posicion = self.mapa.posicion_al_azar(&mut self.rng);
+        let parametros = &self.especies.depredador;
+        let mut depredador = Depredador::new(
+            self.contador_depredadores,
+            sexo,
+            parametros.edad_adulta,
+            municipio,
+            posicion,
+            parametros,
+        );
+        // Tambien empiezan bien comidos, si no los primeros dias se mueren de hambre antes de cazar
+        depredador.reserva_kg = parametros.nivel_optimo_diario;
+        self.depredadores.push(depredador);
+        self.contador_depredadores += 1;
+    }
+
+    // Cuantos de los depredadores pedidos caben segun las reglas del escenario
+    fn cupo_depredadores(&self, cantidad: u32) -> u32 {
+        match self.reglas.max_depredadores {
+            Some(max) => cantidad.min(max.saturating_sub(self.depredadores.len() as u32)),
+            None => cantidad,
         }
     }
 
-    // Funcion para agregar animales (presas), nacen como crías (edad 0) y crecen hasta ser adultas
+    // Funcion para agregar animales (presas) repartidos en los municipios,
+    // los municipios con mas capacidad de carga reciben mas
     pub fn agregar_presas(&mut self, especie: Especie, cantidad: u32) {
-        let mut rng = rand::thread_rng();
         for _ in 0..cantidad {
-            // Por cada presa nueva se suma 1 al contador
-            self.contador_animales += 1;
-            // Con ayuda del anterior rand se le asigna a la presa si va a ser macho o hembra
-            let sexo = if rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
-            self.presas.push(Animal::new(
-                self.contador_animales,
-                especie.clone(),
-                especie.edad_sacrificio(), // Las presas comienzan con edad de sacrificio para ser cazables
-                sexo,
-            ));
+            let municipio = self.region.municipio_al_azar(&mut self.rng);
+            self.agregar_presa(especie.clone(), municipio);
+        }
+    }
+
+    fn agregar_presa(&mut self, especie: Especie, municipio: Municipio) {
+        // Por cada presa nueva se suma 1 al contador
+        self.contador_animales += 1;
+        // Con ayuda del anterior rand se le asigna a la presa si va a ser macho o hembra
+        let sexo = if self.rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
+        // Cada presa empieza en un lugar al azar de su municipio
+        let posicion = self.mapa.posicion_al_azar(&mut self.rng);
+        let parametros = self.especies.de(&especie);
+        self.presas.push(Animal::new(
+            self.contador_animales,
+            especie,
+            parametros.edad_sacrificio(), // Las presas comienzan con edad de sacrificio para ser cazables
+            sexo,
+            municipio,
+            posicion,
+            parametros,
+        ));
+    }
+
+    // Kg de presas que hay en cada municipio (en el orden de Municipio::TODOS)
+    pub fn biomasa_por_municipio(&self) -> Vec<f32> {
+        let mut biomasa = vec![0.0; Municipio::TODOS.len()];
+        for presa in &self.presas {
+            biomasa[presa.municipio.indice()] += presa.peso_kg;
         }
+        biomasa
+    }
+
+    // Temporada del dia actual (antes del primer dia, la del dia 1)
+    pub fn estacion(&self) -> Estacion {
+        Estacion::del_dia(self.dia_actual.max(1))
     }
 
     //Funcion que nos dara todos los detalles de los pasos de los dias
-    pub fn paso_dia(&mut self) -> String {
+    pub fn paso_dia(&mut self) -> ReporteDia {
         self.dia_actual += 1;
-        let mut rng = rand::thread_rng();
-        let mut reporte = format!("=== Día {} ===\n", self.dia_actual);
+        let mut reporte = ReporteDia::new(self.dia_actual);
+        // La temporada cambia el forraje, la reproduccion y la enfermedad de las presas
+        reporte.estacion = self.estacion();
+        let factores = *self.especies.estaciones.de(reporte.estacion);
+        self.observadores.emitir(&Evento::InicioDia { dia: self.dia_actual });
 
         // Envejecer depredadores
         for dep in &mut self.depredadores {
             dep.envejecer_un_dia();
         }
 
+        // Los que tienen un cadaver se quedan comiendo: no caminan, no migran y no cazan
+        let comiendo: Vec<u32> = self.cadaveres.iter().map(|c| c.depredador_id).collect();
+
+        // Todos caminan un poco en una direccion al azar
+        for dep in &mut self.depredadores {
+            if comiendo.contains(&dep.id) {
+                continue;
+            }
+            let distancia = self.especies.depredador.desplazamiento_diario;
+            dep.posicion = self.mapa.mover_al_azar(dep.posicion, distancia, &mut self.rng);
+        }
+        for presa in &mut self.presas {
+            let distancia = self.especies.de(&presa.especie).desplazamiento_diario;
+            presa.posicion = self.mapa.mover_al_azar(presa.posicion, distancia, &mut self.rng);
+        }
+
+        // Migracion entre municipios vecinos. Las presas se van mas cuando su municipio esta lleno
+        // y prefieren vecinos con buen habitat y espacio; los depredadores con hambre se van
+        // el doble y prefieren los vecinos con mas presas
+        let biomasa = self.biomasa_por_municipio();
+        for presa in &mut self.presas {
+            let parche = self.region.parche(presa.municipio);
+            let ocupacion = parche.ocupacion(biomasa[presa.municipio.indice()]);
+            let probabilidad = self.especies.de(&presa.especie).probabilidad_migracion * (1.0 + ocupacion);
+            if self.rng.gen_bool(probabilidad.min(1.0) as f64) {
+                let desde = presa.municipio;
+                presa.municipio = self.region.vecino_al_azar(
+                    desde,
+                    |p| p.atractivo(biomasa[p.municipio.indice()]),
+                    &mut self.rng,
+                );
+                presa.posicion = self.mapa.posicion_al_azar(&mut self.rng);
+                let migracion = Migracion { id: presa.id, es_depredador: false, desde, hacia: presa.municipio };
+                self.observadores.emitir(&Evento::Migracion(migracion.clone()));
+                reporte.migraciones.push(migracion);
+            }
+        }
+        for dep in &mut self.depredadores {
+            if comiendo.contains(&dep.id) {
+                continue;
+            }
+            let mut probabilidad = self.especies.depredador.probabilidad_migracion;
+            if dep.necesita_cazar() {
+                probabilidad *= 2.0;
+            }
+            if self.rng.gen_bool(probabilidad.min(1.0) as f64) {
+                let desde = dep.municipio;
+                dep.municipio = self.region.vecino_al_azar(
+                    desde,
+                    |p| biomasa[p.municipio.indice()] + 1.0,
+                    &mut self.rng,
+                );
+                dep.posicion = self.mapa.posicion_al_azar(&mut self.rng);
+                let migracion = Migracion { id: dep.id, es_depredador: true, desde, hacia: dep.municipio };
+                self.observadores.emitir(&Evento::Migracion(migracion.clone()));
+                reporte.migraciones.push(migracion);
+            }
+        }
+
         // Mezclar orden de depredadores, esto se hizo para que cualquier depredador caze no siempre el depredador #0 es el que empiece
         let mut orden = (0..self.depredadores.len()).collect::<Vec<_>>();
-        orden.shuffle(&mut rng);
+        orden.shuffle(&mut self.rng);
 
         // Cada depredador intenta cazar UNA presa si lo necesita
         for &i in &orden {
             let dep = &mut self.depredadores[i];
-            // Si necesita cazar (reserva menor al óptimo)
-            if dep.necesita_cazar() {
+            // Si necesita cazar (reserva menor al óptimo) y no tiene presa de dias anteriores
+            if dep.necesita_cazar() && !comiendo.contains(&dep.id) {
                 // Buscar presas que pueden ser cazadas (han alcanzado edad de sacrificio)
-                let mut presas_cazables: Vec<(usize, f32)> = self.presas
+                // y que estan lo bastante cerca del depredador, en su mismo municipio
+                let radio_caza = self.especies.depredador.radio_caza;
+                let (indices, candidatas): (Vec<usize>, Vec<&Animal>) = self.presas
                     .iter()
                     .enumerate()
                     .filter(|(_, presa)| presa.puede_ser_cazado())
-                    .map(|(idx, presa)| (idx, presa.peso_kg))
-                    .collect();
-
-                if !presas_cazables.is_empty() {
-                    // Ordenar por peso (descendente) para obtener la más pesada
-                    presas_cazables.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-                    
-                    // Obtener todas las presas con el peso máximo (en caso de empate)
-                    let peso_maximo = presas_cazables[0].1;
-                    let presas_con_peso_maximo: Vec<usize> = presas_cazables
-                        .iter()
-                        .filter(|(_, peso)| (*peso - peso_maximo).abs() < 0.01) // Tolerancia para empates
-                        .map(|(idx, _)| *idx)
-                        .collect();
-
-                    // En caso de empate, elegir una al azar
-                    let pos_elegida = if presas_con_peso_maximo.len() == 1 {
-                        presas_con_peso_maximo[0]
-                    } else {
-                        *presas_con_peso_maximo.choose(&mut rng).unwrap()
-                    };
+                    .filter(|(_, presa)| presa.municipio == dep.municipio)
+                    .filter(|(_, presa)| presa.posicion.distancia(&dep.posicion) <= radio_caza)
+                    .unzip();
 
-                    // Cazar la presa seleccionada
-                    let presa = self.presas.remove(pos_elegida);
-                    dep.cazar(&presa);
-                    // reporta la presa que cazo, cuanto peso y que especie fue
-                    reporte.push_str(&format!(
-                        "Depredador #{} cazó presa ID {} ({:?}, {:.2} kg, {} días).\n",
-                        dep.id, presa.id, presa.especie, presa.peso_kg, presa.edad_dias
-                    ));
+                // La estrategia del depredador escoge cual. Una propia puede devolver una posicion
+                // que no existe, eso cuenta como no escoger ninguna
+                let elegida = self
+                    .estrategias
+                    .elegir(&dep.estrategia, dep, &candidatas, &self.especies.depredador, &mut self.rng)
+                    .and_then(|pos| indices.get(pos).copied());
+                let Some(pos_elegida) = elegida else {
+                    continue;
+                };
+
+                // El ataque puede fallar, segun la especie, el peso, la edad y la manada de la presa
+                let objetivo = &self.presas[pos_elegida];
+                let parametros_presa = self.especies.de(&objetivo.especie);
+                let vecinos_manada = self.presas
+                    .iter()
+                    .filter(|otra| otra.id != objetivo.id && otra.especie == objetivo.especie)
+                    .filter(|otra| otra.municipio == objetivo.municipio)
+                    .filter(|otra| otra.posicion.distancia(&objetivo.posicion) <= parametros_presa.defensa.radio_manada)
+                    .count();
+                let probabilidad = objetivo.probabilidad_ser_cazado(parametros_presa, vecinos_manada);
+                if !self.rng.gen_bool(probabilidad as f64) {
+                    dep.gastar_ataque_fallido(self.especies.depredador.costo_caza_fallida_kg);
+                    let fallida = CazaFallida {
+                        depredador_id: dep.id,
+                        presa_id: objetivo.id,
+                        especie: objetivo.especie.clone(),
+                        peso_kg: objetivo.peso_kg,
+                        probabilidad,
+                    };
+                    self.observadores.emitir(&Evento::CazaFallida(fallida.clone()));
+                    reporte.cazas_fallidas.push(fallida);
+                    continue;
                 }
+
+                // Cazar la presa seleccionada
+                let presa = self.presas.remove(pos_elegida);
+                dep.posicion = presa.posicion;
+                // Solo parte del peso se puede comer, y eso queda en el cadaver
+                let carne_kg = presa.peso_kg * self.especies.de(&presa.especie).fraccion_comestible;
+                self.cadaveres.push(Cadaver {
+                    depredador_id: dep.id,
+                    presa_id: presa.id,
+                    especie: presa.especie.clone(),
+                    carne_kg,
+                });
+                // reporta la presa que cazo, cuanto peso y que especie fue
+                let caza = Caza {
+                    depredador_id: dep.id,
+                    presa_id: presa.id,
+                    especie: presa.especie.clone(),
+                    peso_kg: presa.peso_kg,
+                    edad_dias: presa.edad_dias,
+                    carne_kg,
+                };
+                self.observadores.emitir(&Evento::Caza(caza.clone()));
+                reporte.cazas.push(caza);
+            }
+        }
+
+        // Cada depredador come de su cadaver lo que le quepa
+        for cadaver in &mut self.cadaveres {
+            if let Some(dep) = self.depredadores.iter_mut().find(|d| d.id == cadaver.depredador_id) {
+                cadaver.carne_kg -= dep.comer(cadaver.carne_kg, &self.especies.depredador);
             }
         }
 
@@ -114,112 +394,543 @@ impl Mundo {
             let comio = dep.consumir_diario();
             // Si el depredador sigue vivo
             // Osea que cumple los requisitos de la funcion en models.rs
-            if dep.esta_vivo() {
+            let dia_dep = if dep.esta_vivo() {
+                vivos.push(dep.clone());
                 //Si el depredador esta vivo cada dia que pase va a consumir el nivel mínimo de la reserva
                 if comio {
-                    reporte.push_str(&format!(
-                        "Depredador #{} consumió {:.1} kg. Reserva: {:.2} kg\n",
-                        dep.id, dep.nivel_minimo_diario, dep.reserva_kg
-                    ));
+                    DiaDepredador::Comio {
+                        depredador_id: dep.id,
+                        consumido_kg: dep.nivel_minimo_diario,
+                        reserva_kg: dep.reserva_kg,
+                    }
                 } else {
-                    reporte.push_str(&format!(
-                        "Depredador #{} no comió (lleva {} días sin comer). Reserva: {:.2} kg\n",
-                        dep.id, dep.dias_sin_comer, dep.reserva_kg
-                    ));
+                    DiaDepredador::NoComio {
+                        depredador_id: dep.id,
+                        dias_sin_comer: dep.dias_sin_comer,
+                        reserva_kg: dep.reserva_kg,
+                    }
                 }
-                vivos.push(dep.clone());
             } else {
                 // Si queda fuera de la lisra de esta vivo, es porque se murio
-                reporte.push_str(&format!(
-                    "Depredador #{} murió (Edad: {} días, Días sin comer: {}).\n",
-                    dep.id, dep.edad_dias, dep.dias_sin_comer
-                ));
-            }
+                DiaDepredador::Murio {
+                    depredador_id: dep.id,
+                    edad_dias: dep.edad_dias,
+                    dias_sin_comer: dep.dias_sin_comer,
+                }
+            };
+            self.observadores.emitir(&Evento::Depredador(dia_dep.clone()));
+            reporte.depredadores_dia.push(dia_dep);
         }
         self.depredadores = vivos;
 
-        // Envejecer presas y verificar enfermedades
+        // La carne que queda se pudre un poco cada dia. Los cadaveres que ya no tienen carne
+        // o cuyo depredador murio se pierden del todo
+        let descomposicion = self.especies.depredador.descomposicion_diaria;
+        for cadaver in &mut self.cadaveres {
+            let podrida = cadaver.carne_kg * descomposicion;
+            cadaver.carne_kg -= podrida;
+            reporte.carne_desperdiciada_kg += podrida;
+        }
+        let depredadores = &self.depredadores;
+        let (quedan, perdidos): (Vec<Cadaver>, Vec<Cadaver>) = self.cadaveres
+            .drain(..)
+            .partition(|c| c.carne_kg >= 0.01 && depredadores.iter().any(|d| d.id == c.depredador_id));
+        self.cadaveres = quedan;
+        reporte.carne_desperdiciada_kg += perdidos.iter().map(|c| c.carne_kg).sum::<f32>();
+        reporte.carne_cadaveres_kg = self.cadaveres.iter().map(|c| c.carne_kg).sum();
+
+        // Reproducción de depredadores: una hembra adulta necesita que haya algun macho adulto
+        // en su municipio, y entre mas reserva tenga mas probable es que tenga crias
+        let mut hay_macho = [false; Municipio::TODOS.len()];
+        for dep in &self.depredadores {
+            if dep.sexo == Sexo::Macho && dep.es_adulto() {
+                hay_macho[dep.municipio.indice()] = true;
+            }
+        }
+        let mut nuevos_depredadores = Vec::new();
+        // Si el escenario tiene un maximo de depredadores, no nacen mas de los que caben
+        let mut cupo = self.cupo_depredadores(u32::MAX);
+        {
+            let parametros = &self.especies.depredador;
+            for madre in &mut self.depredadores {
+                if madre.sexo != Sexo::Hembra || !madre.es_adulto() || !hay_macho[madre.municipio.indice()] {
+                    continue;
+                }
+                if !self.rng.gen_bool(madre.probabilidad_reproduccion(parametros) as f64) {
+                    continue;
+                }
+                let cantidad = (self.rng.gen_range(1..=parametros.max_crias_por_parto) as u32).min(cupo);
+                cupo -= cantidad;
+                for _ in 0..cantidad {
+                    let sexo = if self.rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
+                    let mut cria = Depredador::new(
+                        self.contador_depredadores,
+                        sexo,
+                        0,
+                        madre.municipio,
+                        madre.posicion,
+                        parametros,
+                    );
+                    self.contador_depredadores += 1;
+                    cria.estrategia = madre.estrategia.clone();
+                    // La madre le pasa parte de su reserva a cada cria
+                    let reserva = parametros.reserva_cria_kg.min(madre.reserva_kg);
+                    madre.reserva_kg -= reserva;
+                    cria.reserva_kg = reserva;
+                    let nacimiento = NacimientoDepredador {
+                        cria_id: cria.id,
+                        madre_id: madre.id,
+                        sexo: cria.sexo.clone(),
+                    };
+                    self.observadores.emitir(&Evento::NacimientoDepredador(nacimiento.clone()));
+                    reporte.nacimientos_depredadores.push(nacimiento);
+                    nuevos_depredadores.push(cria);
+                }
+            }
+        }
+        self.depredadores.extend(nuevos_depredadores);
+
+        // Pastoreo: las presas de cada municipio se comen el forraje segun su peso.
+        // Si no alcanza para todas, todas comen la misma fraccion de lo que necesitaban
+        let mut demanda = vec![0.0; Municipio::TODOS.len()];
+        for presa in &self.presas {
+            demanda[presa.municipio.indice()] += presa.demanda_forraje(self.especies.de(&presa.especie));
+        }
+        let mut alimentacion = vec![1.0; Municipio::TODOS.len()];
+        for parche in &mut self.region.parches {
+            let i = parche.municipio.indice();
+            alimentacion[i] = parche.pastorear(demanda[i]);
+            parche.rebrotar(factores.forraje);
+        }
+
+        // Envejecer presas (crecen segun lo que comieron)
+        for presa in &mut self.presas {
+            presa.envejecer_un_dia(alimentacion[presa.municipio.indice()]);
+        }
+
+        // Enfermedad (modelo SIR). Las susceptibles se contagian de las infectadas de su especie
+        // que esten cerca en el mismo municipio (o de afuera); las infectadas se recuperan
+        // o mueren al terminar la infeccion
+        let mut infectadas: BTreeMap<(Especie, Municipio), Vec<Posicion>> = BTreeMap::new();
+        for presa in &self.presas {
+            if let EstadoSalud::Infectado { .. } = presa.salud {
+                infectadas.entry((presa.especie.clone(), presa.municipio)).or_default().push(presa.posicion);
+            }
+        }
+        let mut contagiadas = Vec::new();
+        for (idx, presa) in self.presas.iter().enumerate() {
+            if presa.salud != EstadoSalud::Susceptible {
+                continue;
+            }
+            let parametros = self.especies.de(&presa.especie);
+            let vecinos_infectados = infectadas
+                .get(&(presa.especie.clone(), presa.municipio))
+                .map_or(0, |posiciones| {
+                    posiciones
+                        .iter()
+                        .filter(|p| p.distancia(&presa.posicion) <= parametros.enfermedad.radio_contagio)
+                        .count()
+                });
+            let probabilidad = parametros
+                .enfermedad
+                .probabilidad_contagio(vecinos_infectados, parametros.probabilidad_enfermedad(), factores.enfermedad);
+            if self.rng.gen_bool(probabilidad as f64) {
+                contagiadas.push((idx, vecinos_infectados));
+            }
+        }
         let mut presas_enfermas = Vec::new();
         for (idx, presa) in self.presas.iter_mut().enumerate() {
-            presa.envejecer_un_dia();
-            // Verificar si se enferma (probabilidad diaria)
-            if presa.verificar_enfermedad() {
-                presas_enfermas.push(idx);
+            match presa.avanzar_infeccion(&self.especies.de(&presa.especie).enfermedad, &mut self.rng) {
+                Some(FinInfeccion::Murio) => presas_enfermas.push(idx),
+                Some(FinInfeccion::Recupero) => {
+                    let recuperacion = Recuperacion {
+                        presa_id: presa.id,
+                        especie: presa.especie.clone(),
+                    };
+                    self.observadores.emitir(&Evento::Recuperacion(recuperacion.clone()));
+                    reporte.recuperaciones.push(recuperacion);
+                }
+                None => {}
             }
         }
+        // Las nuevas se marcan despues para que su infeccion dure los dias completos
+        for (idx, vecinos_infectados) in contagiadas {
+            let presa = &mut self.presas[idx];
+            presa.salud = EstadoSalud::Infectado {
+                dias_restantes: self.especies.de(&presa.especie).enfermedad.dias_infeccion,
+            };
+            let contagio = Contagio {
+                presa_id: presa.id,
+                especie: presa.especie.clone(),
+                vecinos_infectados,
+            };
+            self.observadores.emitir(&Evento::Contagio(contagio.clone()));
+            reporte.contagios.push(contagio);
+        }
 
-        // Remover presas que se enfermaron
+        // Remover presas que murieron de la enfermedad
         if !presas_enfermas.is_empty() {
             presas_enfermas.sort_unstable();
             presas_enfermas.reverse(); // Remover desde el final para mantener índices válidos
             for idx in presas_enfermas {
                 let presa_enferma = self.presas.remove(idx);
-                reporte.push_str(&format!(
-                    "Presa ID {} ({:?}) murió por enfermedad.\n",
-                    presa_enferma.id, presa_enferma.especie
-                ));
+                let muerte = MuertePresa {
+                    presa_id: presa_enferma.id,
+                    especie: presa_enferma.especie,
+                    edad_dias: presa_enferma.edad_dias,
+                };
+                self.observadores.emitir(&Evento::MuerteEnfermedad(muerte.clone()));
+                reporte.muertes_enfermedad.push(muerte);
             }
         }
 
         // Filtrar presas vivas (muerte por vejez), si no lo estan los elimina de la lista
-        self.presas.retain(|p| p.edad_dias < p.especie.edad_maxima());
+        let (vivas, viejas): (Vec<Animal>, Vec<Animal>) = self.presas
+            .drain(..)
+            .partition(|p| p.edad_dias < self.especies.de(&p.especie).edad_maxima());
+        self.presas = vivas;
+        for presa in viejas {
+            let muerte = MuertePresa {
+                presa_id: presa.id,
+                especie: presa.especie,
+                edad_dias: presa.edad_dias,
+            };
+            self.observadores.emitir(&Evento::MuerteVejez(muerte.clone()));
+            reporte.muertes_vejez.push(muerte);
+        }
 
-        // Reproducción
+        // Las que llevan muchos dias sin comer lo suficiente se mueren de hambre
+        let (vivas, desnutridas): (Vec<Animal>, Vec<Animal>) = self.presas
+            .drain(..)
+            .partition(|p| !p.murio_de_hambre(self.especies.de(&p.especie)));
+        self.presas = vivas;
+        for presa in desnutridas {
+            let muerte = MuertePresa {
+                presa_id: presa.id,
+                especie: presa.especie,
+                edad_dias: presa.edad_dias,
+            };
+            self.observadores.emitir(&Evento::MuerteHambre(muerte.clone()));
+            reporte.muertes_hambre.push(muerte);
+        }
+
+        // Reproducción. Primero paren las hembras que terminan la gestacion; las que estan
+        // disponibles pueden quedar preñadas, frenado por la capacidad de carga y la calidad
+        // del habitat de cada municipio y por lo que alcanzo a comer la madre
+        let biomasa = self.biomasa_por_municipio();
+        // Machos adultos de cada especie en cada municipio, y cuantas hembras ha cubierto cada uno hoy
+        let mut machos: BTreeMap<(Especie, Municipio), Vec<usize>> = BTreeMap::new();
+        for (idx, presa) in self.presas.iter().enumerate() {
+            if presa.sexo == Sexo::Macho && presa.edad_dias >= self.especies.de(&presa.especie).edad_adulta() {
+                machos.entry((presa.especie.clone(), presa.municipio)).or_default().push(idx);
+            }
+        }
+        let mut cubiertas = vec![0; self.presas.len()];
         let mut nuevas_presas = Vec::new();
-        for presa in &self.presas {
-            //Si la presa es mayor y es hembra puede crear nuevos presas
-            if presa.edad_dias >= presa.especie.edad_adulta() && presa.sexo == Sexo::Hembra {
-                if rng.gen_bool(presa.especie.tasa_reproduccion_diaria() as f64) {
-                    let cantidad = rng.gen_range(1..=presa.especie.max_crias_por_parto());
-                    for _ in 0..cantidad {
-                        self.contador_animales += 1;
-                        let sexo = if rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
-                        nuevas_presas.push(Animal::new(
-                            self.contador_animales,
-                            presa.especie.clone(),
-                            0,
-                            sexo,
-                        ));
-                    }
+        for idx in 0..self.presas.len() {
+            let parametros = self.especies.de(&self.presas[idx].especie);
+            if let Some(padre_id) = self.presas[idx].avanzar_ciclo_reproductivo(parametros) {
+                let madre = &self.presas[idx];
+                let cantidad = self.rng.gen_range(1..=parametros.max_crias_por_parto());
+                for _ in 0..cantidad {
+                    self.contador_animales += 1;
+                    let sexo = if self.rng.gen_bool(0.5) { Sexo::Macho } else { Sexo::Hembra };
+                    let nacimiento = Nacimiento {
+                        cria_id: self.contador_animales,
+                        madre_id: madre.id,
+                        padre_id,
+                        especie: madre.especie.clone(),
+                        sexo: sexo.clone(),
+                    };
+                    self.observadores.emitir(&Evento::Nacimiento(nacimiento.clone()));
+                    reporte.nacimientos.push(nacimiento);
+                    nuevas_presas.push(Animal::new(
+                        self.contador_animales,
+                        madre.especie.clone(),
+                        0,
+                        sexo,
+                        madre.municipio, // La cria nace donde esta la madre
+                        madre.posicion,
+                        parametros,
+                    ));
                 }
+                continue;
+            }
+
+            let presa = &self.presas[idx];
+            let factor_habitat = self.region
+                .parche(presa.municipio)
+                .factor_reproduccion(biomasa[presa.municipio.indice()]);
+            //Si la presa es mayor, es hembra y no esta preñada ni recien parida puede quedar preñada
+            if presa.edad_dias >= parametros.edad_adulta()
+                && presa.sexo == Sexo::Hembra
+                && presa.reproduccion == EstadoReproductivo::Disponible
+                && self.rng.gen_bool(
+                    (parametros.tasa_reproduccion_diaria() * factores.reproduccion * factor_habitat * presa.alimentacion)
+                        .min(1.0) as f64,
+                )
+            {
+                // Sin un macho adulto cerca no hay preñez
+                let candidatos = machos
+                    .get(&(presa.especie.clone(), presa.municipio))
+                    .map(|v| v.as_slice())
+                    .unwrap_or(&[]);
+                let Some(padre) = elegir_padre(
+                    presa,
+                    candidatos,
+                    &self.presas,
+                    &cubiertas,
+                    &parametros.apareamiento,
+                    parametros.radio_apareamiento,
+                    &mut self.rng,
+                ) else {
+                    continue;
+                };
+                cubiertas[padre] += 1;
+                let concepcion = Concepcion {
+                    madre_id: presa.id,
+                    padre_id: self.presas[padre].id,
+                    especie: presa.especie.clone(),
+                    dias_gestacion: parametros.dias_gestacion,
+                };
+                self.presas[idx].reproduccion = EstadoReproductivo::Gestante {
+                    padre_id: concepcion.padre_id,
+                    dias_restantes: parametros.dias_gestacion,
+                };
+                self.observadores.emitir(&Evento::Concepcion(concepcion.clone()));
+                reporte.concepciones.push(concepcion);
             }
-        }
-        if !nuevas_presas.is_empty() {
-            reporte.push_str(&format!("Nacieron {} crías.\n", nuevas_presas.len()));
         }
         self.presas.extend(nuevas_presas);
 
         // Resumen de presas
-        let mut mapa: HashMap<&Especie, usize> = HashMap::new();
         for presa in &self.presas {
-            *mapa.entry(&presa.especie).or_insert(0) += 1;
+            *reporte.totales.entry(presa.especie.clone()).or_insert(0) += 1;
+            if presa.esta_gestante() {
+                *reporte.gestantes.entry(presa.especie.clone()).or_insert(0) += 1;
+            }
+            reporte.salud.entry(presa.especie.clone()).or_default().contar(&presa.salud);
         }
 
-        reporte.push_str(&format!(
-            "=== Resumen Día {} ===\nTotal presas: {}\n",
-            self.dia_actual,
-            self.presas.len()
-        ));
-        for (especie, count) in mapa {
-            reporte.push_str(&format!("- {:?}: {}\n", especie, count));
+        // Resumen de depredadores
+        for dep in &self.depredadores {
+            reporte.depredadores.push(ResumenDepredador {
+                id: dep.id,
+                sexo: dep.sexo.clone(),
+                edad_dias: dep.edad_dias,
+                reserva_kg: dep.reserva_kg,
+                estado: dep.estado_alimentacion(),
+                dias_sin_comer: dep.dias_sin_comer,
+                estrategia: dep.estrategia.clone(),
+            });
         }
 
-        // Resumen de depredadores
-        reporte.push_str(&format!("Depredadores vivos: {}\n", self.depredadores.len()));
+        // Resumen por municipio
+        let biomasa = self.biomasa_por_municipio();
+        for presa in &self.presas {
+            let conteo = reporte.municipios.entry(presa.municipio).or_default();
+            *conteo.presas.entry(presa.especie.clone()).or_insert(0) += 1;
+        }
         for dep in &self.depredadores {
-            let estado_alimentacion = if dep.reserva_kg >= dep.nivel_optimo_diario {
-                "Óptimo"
-            } else if dep.reserva_kg >= dep.nivel_minimo_diario {
-                "Mínimo"
-            } else {
-                "Hambriento"
-            };
-            reporte.push_str(&format!(
-                "- Depredador #{} | Edad: {} días | Reserva: {:.2} kg | Estado: {} | Días sin comer: {}\n",
-                dep.id, dep.edad_dias, dep.reserva_kg, estado_alimentacion, dep.dias_sin_comer
-            ));
+            reporte.municipios.entry(dep.municipio).or_default().depredadores += 1;
+        }
+        for (municipio, conteo) in &mut reporte.municipios {
+            conteo.biomasa_kg = biomasa[municipio.indice()];
+            conteo.forraje_kg = self.region.parche(*municipio).forraje_kg;
+            conteo.ocupacion = self.region.parche(*municipio).ocupacion(conteo.biomasa_kg);
         }
 
+        self.historial.registrar(&reporte, &self.presas);
+        self.observadores.emitir(&Evento::FinDia { dia: self.dia_actual });
         reporte
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // Mundo chico con depredadores y las tres presas, tambien lo usan las pruebas de persistencia
+    pub(crate) fn mundo_de_prueba(semilla: u64) -> Mundo {
+        let mut mundo = Mundo::with_seed(semilla);
+        mundo.agregar_depredadores(4);
+        mundo.agregar_presas(Especie::Conejo, 200);
+        mundo.agregar_presas(Especie::Cabra, 50);
+        mundo.agregar_presas(Especie::Vaca, 20);
+        mundo
+    }
+
+    fn reportes(mundo: &mut Mundo, dias: u32) -> Vec<String> {
+        (0..dias).map(|_| mundo.paso_dia().to_string()).collect()
+    }
+
+    #[test]
+    fn la_misma_semilla_da_los_mismos_reportes() {
+        let a = reportes(&mut mundo_de_prueba(42), 60);
+        let b = reportes(&mut mundo_de_prueba(42), 60);
+        assert_eq!(a, b);
+    }
+
+    // Las corridas por lotes reparten mundos entre hilos
+    #[test]
+    fn el_mundo_se_puede_mandar_a_otro_hilo() {
+        let mut mundo = mundo_de_prueba(42);
+        mundo.suscribir(|_: &Evento| {});
+        let dia = std::thread::spawn(move || mundo.paso_dia().dia).join().unwrap();
+        assert_eq!(dia, 1);
+    }
+
+    // Estrategia propia que nunca escoge ninguna presa
+    struct NuncaCaza;
+
+    impl EstrategiaCaza for NuncaCaza {
+        fn elegir(
+            &self,
+            _: &Depredador,
+            _: &[&Animal],
+            _: &crate::especies::ParametrosDepredador,
+            _: &mut dyn rand::RngCore,
+        ) -> Option<usize> {
+            None
+        }
+    }
+
+    // Estrategia propia que se equivoca y escoge una presa que no esta en la lista
+    struct FueraDeRango;
+
+    impl EstrategiaCaza for FueraDeRango {
+        fn elegir(
+            &self,
+            _: &Depredador,
+            candidatas: &[&Animal],
+            _: &crate::especies::ParametrosDepredador,
+            _: &mut dyn rand::RngCore,
+        ) -> Option<usize> {
+            Some(candidatas.len())
+        }
+    }
+
+    fn cazas(mundo: &mut Mundo, dias: u32) -> usize {
+        (0..dias).map(|_| mundo.paso_dia().cazas.len()).sum()
+    }
+
+    #[test]
+    fn usa_las_estrategias_propias_registradas() {
+        let mut normal = Mundo::with_seed(42);
+        normal.agregar_depredadores(4);
+        normal.agregar_presas(Especie::Conejo, 300);
+        assert!(cazas(&mut normal, 30) > 0);
+
+        let mut mundo = Mundo::with_seed(42);
+        mundo.registrar_estrategia("nunca_caza", NuncaCaza);
+        mundo.agregar_depredadores_con(Estrategia::Propia("nunca_caza".to_string()), 4).unwrap();
+        mundo.agregar_presas(Especie::Conejo, 300);
+        assert_eq!(cazas(&mut mundo, 30), 0);
+    }
+
+    #[test]
+    fn una_posicion_fuera_de_las_candidatas_no_caza() {
+        let mut mundo = Mundo::with_seed(42);
+        mundo.registrar_estrategia("fuera_de_rango", FueraDeRango);
+        mundo.agregar_depredadores_con(Estrategia::Propia("fuera_de_rango".to_string()), 4).unwrap();
+        mundo.agregar_presas(Especie::Conejo, 300);
+        assert_eq!(cazas(&mut mundo, 30), 0);
+    }
+
+    #[test]
+    fn las_estrategias_sin_registrar_no_cazan_ni_fallan() {
+        let mut mundo = Mundo::with_seed(42);
+        let propia = Estrategia::Propia("x".to_string());
+        assert_eq!(mundo.agregar_depredadores_con(propia.clone(), 4), Err(EstrategiaNoRegistrada("x".to_string())));
+        assert!(mundo.depredadores.is_empty());
+
+        // Como queda un mundo cargado de un archivo antes de registrar sus estrategias
+        mundo.agregar_depredadores(4);
+        mundo.agregar_presas(Especie::Conejo, 300);
+        for dep in &mut mundo.depredadores {
+            dep.estrategia = propia.clone();
+        }
+        assert_eq!(mundo.estrategias_sin_registrar(), vec!["x".to_string()]);
+        assert_eq!(cazas(&mut mundo, 30), 0);
+    }
+
+    // Una pareja adulta en Medellin, sin presas pero con la reserva llena todos los dias
+    fn nacimientos_con_reserva_llena(sexos: [Sexo; 2], dias: u32) -> usize {
+        let mut mundo = Mundo::with_seed(7);
+        let parametros = mundo.especies.depredador.clone();
+        for (id, sexo) in sexos.into_iter().enumerate() {
+            let posicion = mundo.mapa.posicion_al_azar(&mut mundo.rng);
+            let mut dep = Depredador::new(id as u32, sexo, parametros.edad_adulta, Municipio::Medellin, posicion, &parametros);
+            dep.reserva_kg = parametros.reserva_maxima_kg;
+            mundo.depredadores.push(dep);
+        }
+        mundo.contador_depredadores = 2;
+        let mut nacimientos = 0;
+        for _ in 0..dias {
+            nacimientos += mundo.paso_dia().nacimientos_depredadores.len();
+            for dep in &mut mundo.depredadores {
+                dep.reserva_kg = parametros.reserva_maxima_kg;
+            }
+        }
+        nacimientos
+    }
+
+    #[test]
+    fn una_pareja_bien_comida_tiene_crias() {
+        assert!(nacimientos_con_reserva_llena([Sexo::Macho, Sexo::Hembra], 365) > 0);
+        // Sin macho no hay crias
+        assert_eq!(nacimientos_con_reserva_llena([Sexo::Hembra, Sexo::Hembra], 365), 0);
+    }
+
+    // Contagios en 60 dias de las primeras 100 presas (las crias nacen susceptibles),
+    // con todas empezando en `salud` y la enfermedad llegando de afuera todos los dias
+    fn contagios_de(salud: EstadoSalud) -> usize {
+        let mut especies = ConfigEspecies::default();
+        especies.conejo.probabilidad_enfermedad = 1.0;
+        especies.conejo.enfermedad.tasa_contagio = 1.0;
+        let mut mundo = Mundo::with_config(42, especies);
+        mundo.agregar_presas(Especie::Conejo, 100);
+        let originales: Vec<u32> = mundo.presas.iter().map(|p| p.id).collect();
+        for presa in &mut mundo.presas {
+            presa.salud = salud;
+        }
+        (0..60)
+            .map(|_| mundo.paso_dia().contagios.iter().filter(|c| originales.contains(&c.presa_id)).count())
+            .sum()
+    }
+
+    #[test]
+    fn las_recuperadas_no_se_vuelven_a_contagiar() {
+        assert!(contagios_de(EstadoSalud::Susceptible) > 0);
+        assert_eq!(contagios_de(EstadoSalud::Recuperado), 0);
+    }
+
+    // Concepciones en 5 dias de 20 conejas adultas junto a 20 conejos de `edad_machos` dias
+    fn concepciones_con_machos_de(edad_machos: u32) -> usize {
+        let mut especies = ConfigEspecies::default();
+        especies.conejo.tasa_reproduccion_diaria = 1.0;
+        let parametros = especies.conejo.clone();
+        let mut mundo = Mundo::with_config(42, especies);
+        let posicion = mundo.mapa.posicion_al_azar(&mut mundo.rng);
+        for id in 0..40 {
+            let (sexo, edad) = if id < 20 { (Sexo::Hembra, parametros.edad_adulta) } else { (Sexo::Macho, edad_machos) };
+            mundo.presas.push(Animal::new(id, Especie::Conejo, edad, sexo, Municipio::Medellin, posicion, &parametros));
+        }
+        mundo.contador_animales = 40;
+        (0..5).map(|_| mundo.paso_dia().concepciones.len()).sum()
+    }
+
+    #[test]
+    fn sin_machos_adultos_no_hay_prenez() {
+        let adulta = ConfigEspecies::default().conejo.edad_adulta;
+        assert!(concepciones_con_machos_de(adulta) > 0);
+        assert_eq!(concepciones_con_machos_de(0), 0);
+    }
+
+    #[test]
+    fn otra_semilla_da_otros_reportes() {
+        let a = reportes(&mut mundo_de_prueba(42), 20);
+        let b = reportes(&mut mundo_de_prueba(43), 20);
+        assert_ne!(a, b);
+    }
+}